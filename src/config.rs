@@ -2,9 +2,20 @@ use serde_derive::{ Deserialize, Serialize };
 
 use crate::utils;
 
+mod best_config;
+mod checkpoint_config;
+mod cost_config;
+mod crosstalk_config;
 mod debug_config;
+mod defrag_config;
+mod failure_config;
+mod ilp_config;
 mod network_config;
 mod policy_config;
+mod power_config;
+mod protection_config;
+mod route_config;
+mod search_config;
 mod simulation_config;
 mod traffic_config;
 
@@ -15,6 +26,28 @@ pub struct Config {
     pub network: network_config::NetworkConfig,
     pub policy: policy_config::PolicyConfig,
     pub traffic: traffic_config::TrafficConfig,
+    #[serde(default)]
+    pub crosstalk: crosstalk_config::CrosstalkConfig,
+    #[serde(default)]
+    pub route: route_config::RouteConfig,
+    #[serde(default)]
+    pub protection: protection_config::ProtectionConfig,
+    #[serde(default)]
+    pub failure: failure_config::FailureConfig,
+    #[serde(default)]
+    pub defrag: defrag_config::DefragConfig,
+    #[serde(default)]
+    pub ilp: ilp_config::IlpConfig,
+    #[serde(default)]
+    pub search: search_config::SearchConfig,
+    #[serde(default)]
+    pub cost: cost_config::CostConfig,
+    #[serde(default)]
+    pub power: power_config::PowerConfig,
+    #[serde(default)]
+    pub best: best_config::BestConfig,
+    #[serde(default)]
+    pub checkpoint: checkpoint_config::CheckpointConfig,
 }
 
 impl Config {
@@ -33,4 +66,45 @@ impl Config {
             Err(_) => panic!("ファイルの読込に失敗しました。"),
         }
     }
-}
\ No newline at end of file
+}
+/// テスト用の設定 (jpn12, FXC, SINGLE, パス需要100本)
+/// テストごとに必要な項目を書き換えて用いる
+#[cfg(test)]
+pub fn test_config() -> Config {
+    toml::from_str(
+        r#"
+        [simulation]
+        traffic_intensity = 1.0
+        random_seed = 1
+        outdir = ""
+        # グラフの描画は行わない
+        pythonexe_path = "true"
+
+        [debug]
+        log_demand_assign = false
+        log_fiber_expand = false
+        log_fiber_remove = false
+        log_bypass = false
+        log_analysis = false
+        log_taboo = false
+        log_state_matrix = false
+
+        [network]
+        topology = "jpn12"
+        wss_m = 20
+        fiber_increase_rate_limit = 0.1
+        design_mode = "SINGLE"
+        node_configuration = "FXC"
+        modification_config_filepath = ""
+        fiber_unification = false
+
+        [policy]
+        routing_policy = "FF"
+
+        [traffic]
+        distribution_filepath = ""
+        path_num = 100
+        "#,
+    )
+    .unwrap()
+}
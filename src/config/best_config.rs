@@ -0,0 +1,35 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// BESTモード (複数シードから最良の設計を選ぶ) の設定
+pub struct BestConfig {
+    /// 選択基準
+    ///
+    /// - "FIBER_RATIO": W-W以外のファイバの割合 (大きいほど良い)
+    /// - "MAX_WXC_SIZE": WXCの最大ポート数
+    /// - "WXC_PASS": 平均WXC通過回数
+    /// - "COST": 設備コスト
+    /// - "WEIGHTED": 各指標をシード間の平均で正規化した値の重み付き和
+    pub objective: String,
+    /// W-Wファイバの割合 (1 - FIBER_RATIO) の重み
+    pub weight_fiber_ratio: f64,
+    /// WXCの最大ポート数の重み
+    pub weight_max_wxc_size: f64,
+    /// 平均WXC通過回数の重み
+    pub weight_wxc_pass: f64,
+    /// 設備コストの重み
+    pub weight_cost: f64,
+}
+
+impl Default for BestConfig {
+    fn default() -> Self {
+        Self {
+            objective: "FIBER_RATIO".to_string(),
+            weight_fiber_ratio: 1.0,
+            weight_max_wxc_size: 1.0,
+            weight_wxc_pass: 1.0,
+            weight_cost: 1.0,
+        }
+    }
+}
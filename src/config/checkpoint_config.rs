@@ -0,0 +1,20 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// チェックポイント (設計の途中経過の保存) の設定
+///
+/// `designer::main`は各バイパス長の開始時と，`interval`回のバイパス設置の試行ごとに
+/// 出力先ディレクトリへ`checkpoint.bin`を書き出す．`--resume <dir>`で続きから再開できる
+pub struct CheckpointConfig {
+    /// チェックポイントを書き出す試行の間隔 (0の場合は書き出さない)
+    pub interval: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            interval: 10,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// 設備コスト (CAPEX) モデルの設定
+///
+/// 単位は任意 (相対値) とし，ファイバはファイバ長，XCはポート数に比例するとする
+pub struct CostConfig {
+    /// `designer::main`でのバイパスの採否の基準 ("FIBER": ファイバ数の比，"COST": 設備コスト)
+    pub criterion: String,
+    /// SCFの1kmあたりのコスト
+    pub scf_per_km: f64,
+    /// MCFの1kmあたりのコスト
+    pub mcf_per_km: f64,
+    /// `Fiber::distance`が0の場合に用いるファイバ長 [km]
+    pub default_fiber_length: f64,
+    /// WXC (補助WXCを含む) の1ポートあたりのコスト
+    pub wxc_port: f64,
+    /// FXC・SXCの1ポートあたりのコスト
+    pub fxc_port: f64,
+    /// WBXCの1波長帯ポートあたりのコスト (WBXCのポート数 × 波長帯数で数える)
+    pub wbxc_waveband_port: f64,
+    /// 送受信器1台あたりのコスト (パス需要の両端に1台ずつ置く)
+    pub transceiver: f64,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            criterion: "FIBER".to_string(),
+            scf_per_km: 1.0,
+            mcf_per_km: 2.5,
+            default_fiber_length: 100.0,
+            wxc_port: 100.0,
+            fxc_port: 10.0,
+            wbxc_waveband_port: 20.0,
+            transceiver: 200.0,
+        }
+    }
+}
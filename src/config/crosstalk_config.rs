@@ -0,0 +1,38 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// マルチコアファイバのコア間クロストーク関連の設定
+pub struct CrosstalkConfig {
+    /// コア配置 (LINEAR, SQUARE, HEXAGONAL)
+    pub core_layout: String,
+    /// 結合係数 κ [1/m]
+    pub coupling_coefficient: f64,
+    /// 曲げ半径 R [m]
+    pub bend_radius: f64,
+    /// 伝搬定数 β [1/m]
+    pub propagation_constant: f64,
+    /// コアピッチ Λ [m]
+    pub core_pitch: f64,
+    /// ファイバ長が未設定(0)の場合に用いるファイバ長 [km]
+    pub default_fiber_length: f64,
+    /// 許容クロストーク [dB]
+    pub threshold_db: f64,
+    /// パス探索時にクロストークによる受付判定を行うか
+    pub admission_check: bool,
+}
+
+impl Default for CrosstalkConfig {
+    fn default() -> Self {
+        Self {
+            core_layout: "SQUARE".to_string(),
+            coupling_coefficient: 4.0e-4,
+            bend_radius: 5.0e-2,
+            propagation_constant: 4.0e6,
+            core_pitch: 4.5e-5,
+            default_fiber_length: 100.0,
+            threshold_db: -32.0,
+            admission_check: false,
+        }
+    }
+}
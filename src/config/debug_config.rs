@@ -17,4 +17,10 @@ pub struct DebugConfig {
     pub log_taboo: bool,
     /// ステップ時にstate_matrixを出力するか
     pub log_state_matrix: bool,
+    /// 動的解析時にスペクトルの断片化指標を出力するか
+    #[serde(default)]
+    pub log_fragmentation: bool,
+    /// 設計の各イテレーション後にNWの不変条件を検証するか (違反があれば停止する)
+    #[serde(default)]
+    pub validate_network: bool,
 }
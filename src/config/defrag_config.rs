@@ -0,0 +1,24 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// 動的解析におけるデフラグメンテーション関連の設定
+pub struct DefragConfig {
+    /// 実行契機 (NONE, PERIODIC, BLOCKING)
+    /// PERIODICは`interval`回の到着ごと，BLOCKINGは呼損の発生時に実行し，その後同じDemandを再度割り当てる
+    pub trigger: String,
+    /// PERIODICの場合の実行間隔 (到着数)
+    pub interval: usize,
+    /// 1回のデフラグメンテーションで再割当するDemand数の上限
+    pub max_moves: usize,
+}
+
+impl Default for DefragConfig {
+    fn default() -> Self {
+        Self {
+            trigger: "NONE".to_string(),
+            interval: 100,
+            max_moves: 10,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+/// 故障シミュレーション関連の設定
+pub struct FailureConfig {
+    /// 全ての単一リンク故障を評価するか
+    pub link_failure: bool,
+    /// 全ての単一ノード故障 (ノード上の全XCの故障) を評価するか
+    pub node_failure: bool,
+    /// 全ての単一XC故障を評価するか
+    pub xc_failure: bool,
+    /// 全ての単一SRLG故障 (SRLGに属するリンクの同時故障) を評価するか
+    pub srlg_failure: bool,
+}
@@ -0,0 +1,12 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+/// バイパス配置問題のILP関連の設定
+pub struct IlpConfig {
+    /// 設計時にILP (LP形式) を出力ディレクトリへ書き出すかどうか
+    pub export: bool,
+    /// ソルバの解ファイルのパス，`design_mode = "ILP"`の場合に読み込む
+    /// 空の場合はILPの出力のみを行う
+    pub solution_filepath: String,
+}
@@ -6,7 +6,11 @@ pub struct NetworkConfig {
     /// 対象物理トポロジ
     pub topology: String,
     /// WSSのサイズ、1xM
+    /// WXCの方路数がMを超える場合，WSSを多段接続するものとして扱う
     pub wss_m: usize,
+    /// WSS 1段あたりの挿入損失 [dB]
+    #[serde(default = "default_wss_insertion_loss")]
+    pub wss_insertion_loss: f64,
     /// ループ時ファイバ増加率判定値
     pub fiber_increase_rate_limit: f64,
     /// 設計モード
@@ -16,5 +20,20 @@ pub struct NetworkConfig {
     /// 改造コンフィグ
     pub modification_config_filepath: String,
     /// ファイバをまとめるかどうか
-    pub fiber_unification: bool
+    pub fiber_unification: bool,
+    /// 三層構成 (WBXC-FXC) において，ファイババイパスを選ぶパス数のスロット数に対する比率
+    #[serde(default = "default_fiber_bypass_ratio")]
+    pub fiber_bypass_ratio: f64,
+    /// バイパス設置時に，影響を受けるパス需要のみを再割当するかどうか
+    /// falseの場合は反復ごとに全てのパスを削除して割り当て直す
+    #[serde(default)]
+    pub incremental_reassignment: bool,
+}
+
+fn default_fiber_bypass_ratio() -> f64 {
+    0.5
+}
+
+fn default_wss_insertion_loss() -> f64 {
+    6.0
 }
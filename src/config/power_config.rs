@@ -0,0 +1,31 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// 消費電力モデルの設定 [W]
+///
+/// XCの静的な消費電力と，パス需要ごとのトランスポンダの消費電力を考える
+pub struct PowerConfig {
+    /// WXC (補助WXCを含む) を構成する1xMのWSS 1台あたり
+    pub wss: f64,
+    /// WXC (補助WXCを含む) の1ポートあたり (WSS以外の制御回路など)
+    pub wxc_port: f64,
+    /// FXC・SXCの1ポートあたり
+    pub fxc_port: f64,
+    /// WBXCの波長帯スイッチ1台あたり (WBXCのポート数 × 波長帯数で数える)
+    pub wbxc_waveband_switch: f64,
+    /// トランスポンダ1台あたり (パス需要の両端に1台ずつ置く)
+    pub transponder: f64,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            wss: 40.0,
+            wxc_port: 5.0,
+            fxc_port: 1.0,
+            wbxc_waveband_switch: 10.0,
+            transponder: 75.0,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// パス保護関連の設定
+pub struct ProtectionConfig {
+    /// 保護方式 (NONE, DEDICATED, SHARED)
+    pub mode: String,
+    /// 現用・予備経路の独立性 (LINK, NODE)
+    pub disjoint_type: String,
+}
+
+impl Default for ProtectionConfig {
+    fn default() -> Self {
+        Self {
+            mode: "NONE".to_string(),
+            disjoint_type: "LINK".to_string(),
+        }
+    }
+}
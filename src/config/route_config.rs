@@ -0,0 +1,30 @@
+use serde_derive::{ Deserialize, Serialize };
+
+use crate::np_core::parameters::{ HOP_SLUG, SHORTEST_K };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// 経路候補探索関連の設定
+pub struct RouteConfig {
+    /// 経路候補の探索手法 (ALL_SIMPLE_PATHS, YEN)
+    /// YENは密なトポロジで高速だが，同じホップ数の経路の順序が異なるため`shortest_k`で打ち切ると経路候補が変わりうる
+    pub search_method: String,
+    /// SDペアあたりの経路候補数の上限
+    pub shortest_k: usize,
+    /// 最短経路のホップ数から許容する追加ホップ数
+    pub hop_slack: usize,
+    /// 経路の重み付け (HOP, DISTANCE)
+    /// DISTANCEの場合は`files/topology/{topology}_distance.txt`を読み込む
+    pub weight: String,
+}
+
+impl Default for RouteConfig {
+    fn default() -> Self {
+        Self {
+            search_method: "ALL_SIMPLE_PATHS".to_string(),
+            shortest_k: SHORTEST_K,
+            hop_slack: HOP_SLUG,
+            weight: "HOP".to_string(),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde_derive::{ Deserialize, Serialize };
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// 局所探索 (焼きなまし法) による設計の設定
+///
+/// 目的関数は従来NWに対する比の重み付き和 (小さいほど良い)
+pub struct SearchConfig {
+    /// 探索時間の上限 [s]
+    pub time_limit: f64,
+    /// 反復回数の上限
+    pub max_iterations: usize,
+    /// 初期温度
+    pub initial_temperature: f64,
+    /// 反復ごとの温度の減衰率
+    pub cooling_rate: f64,
+    /// バイパス追加時に候補とする，出現頻度上位の区間数
+    pub candidate_count: usize,
+    /// ファイバ数の比の重み
+    pub weight_fiber: f64,
+    /// WXCの最大ポート数の比の重み
+    pub weight_max_wxc_size: f64,
+    /// 平均WXC通過回数の比の重み
+    pub weight_wxc_pass: f64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            time_limit: 60.0,
+            max_iterations: 1000,
+            initial_temperature: 0.05,
+            cooling_rate: 0.99,
+            candidate_count: 5,
+            weight_fiber: 1.0,
+            weight_max_wxc_size: 1.0,
+            weight_wxc_pass: 1.0,
+        }
+    }
+}
@@ -9,5 +9,8 @@ pub struct SimulationConfig {
     pub random_seed: u64,
     /// 統計情報出力先フォルダ
     pub outdir: String,
-    pub pythonexe_path: String
+    pub pythonexe_path: String,
+    /// `--resume`で起動した場合，`outdir`のチェックポイントから設計を再開する (設定ファイルには書かない)
+    #[serde(skip)]
+    pub resume: bool,
 }
@@ -9,6 +9,10 @@ pub mod dynamic;
 
 pub mod ctrl_utils;
 
+mod failure;
+
+mod checkpoint;
+
 fn get_expand_edges(network: &Network, node_route: &[usize]) -> Vec<Edge> {
     // ノードルート->エッジルート
     let edge_route = {
@@ -81,21 +85,79 @@ fn get_expand_edges(network: &Network, node_route: &[usize]) -> Vec<Edge> {
     out
 }
 
+/// ノード構成の文字列から，ノードを構成するXCの種類を得る
+///
+/// 対応していないノード構成はここで弾くため，以降の設計・経路探索に渡るXCの組み合わせは
+/// この関数と`expander::select_bypass_xc_types`が返すものに限られる．
+pub fn parse_node_configuration(node_configuration: &str) -> &'static [XCType] {
+    match node_configuration.to_uppercase().as_str() {
+        "FXC"  => &[XCType::Wxc, XCType::Fxc],
+        "SXC"  => &[XCType::Wxc, XCType::Sxc],
+        "WBXC" => &[XCType::Wxc, XCType::Wbxc],
+        // 階層型三層ノード，WBバイパスとファイババイパスを併用する
+        "WBXC-FXC" => &[XCType::Wxc, XCType::Wbxc, XCType::Fxc],
+        // ファイババイパスの入口に補助WXCを置き，バイパスへ向かうパスを集約する
+        "ADDED_WXC-FXC" => &[XCType::Wxc, XCType::Added_Wxc, XCType::Fxc],
+        // "FXC-SXC" => [XCType::Wxc, XCType::Fxc, XCType::Sxc],
+        _ => panic!(
+            "Invalid `node_configuration`: {} (supported: FXC, SXC, WBXC, WBXC-FXC, ADDED_WXC-FXC)",
+            node_configuration
+        ),
+    }
+}
+
 pub fn main(config: &Config) -> (Network, Topology, String) {
 
-    let xc_types = match config.network.node_configuration.to_uppercase().as_str() {
-        "FXC"  => [XCType::Wxc, XCType::Fxc],
-        "SXC"  => [XCType::Wxc, XCType::Sxc],
-        "WBXC" => [XCType::Wxc, XCType::Wbxc],
-        // "FXC-SXC" => [XCType::Wxc, XCType::Fxc, XCType::Sxc],
-        _ => unimplemented!()
-    };
+    let xc_types = parse_node_configuration(&config.network.node_configuration);
 
     match config.network.design_mode.to_uppercase().as_str() {
-        "BEST" | "best" => designer::iterative_designer::best_main(config, &xc_types),
-        "SINGLE" | "single" | "once" | "ONCE" => designer::main(config, &xc_types),
+        "BEST" | "best" => designer::iterative_designer::best_main(config, xc_types),
+        "SINGLE" | "single" | "once" | "ONCE" => designer::main(config, xc_types),
+        "ILP" | "ilp" => designer::ilp::main(config, xc_types),
+        "SA" | "sa" => designer::local_search::main(config, xc_types),
         "WBXC" | "wbxc" => designer::wxc_wbxc_designer::main(config),
         "AVERAGE" | "average" => designer::iterative_designer::average_main(config),
         _ => panic!("Invalid `design_mode`"),
     }
 }
+
+#[test]
+fn deterministic_output_test() {
+    use std::{collections::BTreeMap, fs, path::Path};
+
+    /// 出力ディレクトリ内の全ファイル (相対パス -> 内容)
+    fn read_output_dir(dir: &Path, root: &Path, files: &mut BTreeMap<String, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                read_output_dir(&path, root, files);
+            } else {
+                let relative_path = path.strip_prefix(root).unwrap().to_string_lossy().to_string();
+                files.insert(relative_path, fs::read(&path).unwrap());
+            }
+        }
+    }
+
+    let outdir = "target/deterministic_output_test";
+    let mut config = crate::config::test_config();
+    config.simulation.random_seed = 7;
+    config.simulation.outdir = outdir.to_string();
+
+    // 同じ設定・シードで2回設計し，出力ディレクトリの全ファイルが一致することを確かめる
+    let outputs: Vec<BTreeMap<String, Vec<u8>>> = (0..2)
+        .map(|_| {
+            let _ = fs::remove_dir_all(outdir);
+            let (_network, _topology, output_dir) = main(&config);
+            let mut files = BTreeMap::new();
+            read_output_dir(Path::new(&output_dir), Path::new(&output_dir), &mut files);
+            files
+        })
+        .collect();
+    let _ = fs::remove_dir_all(outdir);
+
+    assert!(outputs[0].contains_key("prop/path_info.txt"));
+    assert_eq!(outputs[0].keys().collect::<Vec<_>>(), outputs[1].keys().collect::<Vec<_>>());
+    for (path, contents) in &outputs[0] {
+        assert!(outputs[1][path] == *contents, "{} differs between runs", path);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{fs, path::Path};
+
+use serde_derive::{ Deserialize, Serialize };
+
+use crate::{ config::Config, demand::Demand, network::Network, Edge, SD };
+
+use super::failure::FailureResultsByKind;
+
+const CHECKPOINT_FILE: &str = "checkpoint.bin";
+
+/// `designer::main`の途中経過
+///
+/// `bypass_len`のバイパス設置を，`sds`の候補から試行する直前の状態を表す (`sds`が`None`の場合は候補を求め直す)．
+/// バイパス長の途中で保存した場合も，それまでの試行は全て取り消されているため，
+/// 採否の基準 (`AcceptanceBaseline`) は`network`から求め直せる
+#[derive(Debug, Deserialize)]
+pub struct Checkpoint {
+    pub bypass_len: usize,
+    pub sds: Option<Vec<(SD, usize)>>,
+    pub network: Network,
+    pub demand_list: Vec<Demand>,
+    pub taboo_list: Vec<SD>,
+    pub all_installed_edges: Vec<Vec<Edge>>,
+    pub conv_nw_w2w_fiber_count: usize,
+    pub conv_failure_results: FailureResultsByKind,
+}
+
+/// 書き出し用の`Checkpoint` (設計中の値を複製せずに参照する)
+/// bincodeは参照と所有した値を同じ形式で書き出すため，`Checkpoint`として読み込める
+#[derive(Serialize)]
+pub struct CheckpointRef<'a> {
+    pub bypass_len: usize,
+    pub sds: Option<&'a [(SD, usize)]>,
+    pub network: &'a Network,
+    pub demand_list: &'a [Demand],
+    pub taboo_list: &'a [SD],
+    pub all_installed_edges: &'a [Vec<Edge>],
+    pub conv_nw_w2w_fiber_count: usize,
+    pub conv_failure_results: &'a FailureResultsByKind,
+}
+
+/// 試行の回数に応じてチェックポイントを書き出すか
+pub fn is_due(config: &Config, attempt_count: usize) -> bool {
+    config.checkpoint.interval > 0 && attempt_count.is_multiple_of(config.checkpoint.interval)
+}
+
+/// チェックポイントを書き出す
+/// 書き込み中に中断しても直前のチェックポイントが壊れないよう，一時ファイルに書いてから置き換える
+pub fn save(config: &Config, output_dir: &str, checkpoint: &CheckpointRef) {
+    if config.checkpoint.interval == 0 {
+        return;
+    }
+
+    let bytes = match bincode::serialize(checkpoint) {
+        Ok(bytes) => bytes,
+        Err(err) => panic!("チェックポイントの変換に失敗しました: {}", err),
+    };
+    let path = format!("{output_dir}/{CHECKPOINT_FILE}");
+    let tmp_path = format!("{path}.tmp");
+    match fs::write(&tmp_path, bytes) {
+        Ok(_) => (),
+        Err(_) => panic!("チェックポイントを書き込めませんでした"),
+    }
+    match fs::rename(&tmp_path, &path) {
+        Ok(_) => (),
+        Err(_) => panic!("チェックポイントを置き換えられませんでした"),
+    }
+}
+
+/// `--resume`で起動した場合，`output_dir`のチェックポイントを読み込む
+/// チェックポイントがない場合 (書き出す前に中断した場合) は`None`を返し，最初から設計する
+pub fn load(config: &Config, output_dir: &str) -> Option<Checkpoint> {
+    let path = format!("{output_dir}/{CHECKPOINT_FILE}");
+    if !config.simulation.resume || !Path::new(&path).exists() {
+        return None;
+    }
+
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(_) => panic!("チェックポイントの読込に失敗しました: {}", path),
+    };
+    match bincode::deserialize(&bytes) {
+        Ok(checkpoint) => Some(checkpoint),
+        Err(err) => panic!("チェックポイントのパースに失敗しました: {}: {}", path, err),
+    }
+}
@@ -5,12 +5,13 @@ use crate::{
     config::Config,
     debugger,
     demand::Demand,
-    network::{Fiber, FiberID, Network},
+    network::{protection::{disjoint_type_from_config, ProtectionMode}, Fiber, FiberID, Network},
     np_core::parameters::{ PB_CHARS, PB_TEMPLATES },
-    topology::{get_random_shortest_path, Topology},
+    topology::{get_random_shortest_path, DisjointRoutePair, Topology},
+    Edge,
 };
 
-use super::{expander, get_expand_edges, pathfinder };
+use super::{expander, get_expand_edges, pathfinder::{self, AssignmentInstruction} };
 
 pub fn delete_all_paths(network: &mut Network, demand_list: &mut [Demand]) {
     for demand in demand_list {
@@ -18,21 +19,41 @@ pub fn delete_all_paths(network: &mut Network, demand_list: &mut [Demand]) {
     }
 }
 
+/// 指定したインデックスのパス需要のパスを削除する
+pub fn delete_paths(network: &mut Network, demand_list: &mut [Demand], indices: &[usize]) {
+    for &index in indices {
+        delete(&mut demand_list[index], network);
+    }
+}
+
 pub fn assign_all_paths(
     config: &Config,
     network: &mut Network,
     topology: &Topology,
     demand_list: &mut [Demand]
 ) {
-    let pb = ProgressBar::new(demand_list.len() as u64);
+    let indices: Vec<usize> = (0..demand_list.len()).collect();
+    assign_paths(config, network, topology, demand_list, &indices);
+}
+
+/// 指定したインデックスのパス需要のみを，インデックスの順に割り当てる
+/// 収容できない場合は最短経路上のWXCファイバを増設する
+pub fn assign_paths(
+    config: &Config,
+    network: &mut Network,
+    topology: &Topology,
+    demand_list: &mut [Demand],
+    indices: &[usize]
+) {
+    let pb = ProgressBar::new(indices.len() as u64);
     pb.set_style(
         ProgressStyle::default_bar().template(PB_TEMPLATES).unwrap().progress_chars(PB_CHARS)
     );
 
     // 一旦，パスをWXCネットワークに収容，足りない部分は増設
     let mut i = 0;
-    while i < demand_list.len() {
-        let demand = &mut demand_list[i];
+    while i < indices.len() {
+        let demand = &mut demand_list[indices[i]];
 
         if assign(config, demand, topology, network) {
             // debug
@@ -46,12 +67,22 @@ pub fn assign_all_paths(
             // debug
             // debugger::log_state_matrix(config, network);
 
-            // 最短経路上にあるWXCファイバを選択，
-            // let shortest_route_cand = &topology.route_candidates.get(&demand.sd).unwrap()[0];
-            let shortest_route_cand = get_random_shortest_path(topology, &demand.sd, network.rng.gen_range(0..u64::MAX), None);
-
             // 拡張すべきエッジを取得
-            let expand_edges = get_expand_edges(network, &shortest_route_cand.node_route);
+            let expand_edges = match get_protected_route_pair(config, topology, demand) {
+                // パス保護を行う場合は，独立な経路対の両方を拡張対象とする
+                Some(route_pair) => {
+                    let mut expand_edges = get_expand_edges(network, &route_pair.primary.node_route);
+                    expand_edges.extend(get_expand_edges(network, &route_pair.secondary.node_route));
+                    expand_edges
+                }
+                None => {
+                    // 最短経路上にあるWXCファイバを選択，
+                    // let shortest_route_cand = &topology.route_candidates.get(&demand.sd).unwrap()[0];
+                    let shortest_route_cand = get_random_shortest_path(topology, &demand.sd, network.rng.gen_range(0..u64::MAX), None);
+
+                    get_expand_edges(network, &shortest_route_cand.node_route)
+                }
+            };
 
             // 拡張
             // expander::expand_wxc_fibers_with_edge(config, network, &expand_edges);
@@ -70,20 +101,86 @@ pub fn assign(
     network: &mut Network
 ) -> bool {
     if let Some(assignment_instruction) = pathfinder::search(config, demand, topology, network) {
-        network.assign_path(assignment_instruction.slot_head.clone(), &assignment_instruction.fiber_ids, &assignment_instruction.core_indices, demand);
-
-        // Demandへ情報を適用
-        demand.slot_heads = assignment_instruction.slot_head;
-        demand.fiber_ids = assignment_instruction.fiber_ids;
-        demand.slot_width = assignment_instruction.slot_width;
-        demand.core_indices = assignment_instruction.core_indices;
+        apply_assignment_instruction(network, demand, assignment_instruction);
+        true
+    } else {
+        false
+    }
+}
 
+/// 故障からの復旧のため，現用パスのみを再割当する
+pub fn restore(
+    config: &Config,
+    demand: &mut Demand,
+    topology: &Topology,
+    network: &mut Network
+) -> bool {
+    if let Some(assignment_instruction) = pathfinder::search_unprotected(config, demand, topology, network) {
+        apply_assignment_instruction(network, demand, assignment_instruction);
         true
     } else {
         false
     }
 }
 
+pub fn apply_assignment_instruction(network: &mut Network, demand: &mut Demand, assignment_instruction: AssignmentInstruction) {
+    network.assign_path(assignment_instruction.slot_head.clone(), &assignment_instruction.fiber_ids, &assignment_instruction.core_indices, assignment_instruction.backup.as_ref(), demand);
+
+    // Demandへ情報を適用
+    demand.slot_heads = assignment_instruction.slot_head;
+    demand.fiber_ids = assignment_instruction.fiber_ids;
+    demand.slot_width = assignment_instruction.slot_width;
+    demand.core_indices = assignment_instruction.core_indices;
+    demand.backup = assignment_instruction.backup;
+}
+
+/// パス保護を行う場合，Demandの独立な経路対を返す
+/// 保護なし，または独立な経路対が存在しないSDペアの場合は`None`
+pub fn get_protected_route_pair<'a>(
+    config: &Config,
+    topology: &'a Topology,
+    demand: &Demand
+) -> Option<&'a DisjointRoutePair> {
+    match ProtectionMode::from_config(config) {
+        ProtectionMode::Unprotected => None,
+        ProtectionMode::Dedicated | ProtectionMode::Shared => {
+            topology.get_disjoint_route_pair(&demand.sd, disjoint_type_from_config(config))
+        }
+    }
+}
+
+/// バイパスの設置で再割当が必要なパス需要のインデックス
+///
+/// 削除されるファイバを現用・予備パスで使用しているもの，
+/// および現用パスがバイパス区間 (`target_edges`) を連続して通過し，バイパスを利用できるもの
+pub fn get_affected_demand_indices(
+    network: &Network,
+    demand_list: &[Demand],
+    removing_fiber_ids: &[FiberID],
+    target_edges: &[Edge]
+) -> Vec<usize> {
+    demand_list
+        .iter()
+        .enumerate()
+        .filter(|(_, demand)| !demand.slot_heads.is_empty())
+        .filter(|(_, demand)| {
+            let uses_removing_fiber = demand.fiber_ids
+                .iter()
+                .chain(demand.backup.iter().flat_map(|backup| backup.fiber_ids.iter()))
+                .any(|fiber_id| removing_fiber_ids.contains(fiber_id));
+
+            let edge_route: Vec<Edge> = demand.fiber_ids
+                .iter()
+                .map(|fiber_id| network.get_fiber_by_id(fiber_id).edge)
+                .collect();
+            let can_use_bypass = edge_route.windows(target_edges.len()).any(|window| window == target_edges);
+
+            uses_removing_fiber || can_use_bypass
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
 fn delete(demand: &mut Demand, network: &mut Network) {
     if !demand.slot_heads.is_empty() {
         network.remove_path(demand);
@@ -97,7 +194,7 @@ pub fn get_least_utilized_fiber<'a>(
 ) -> Option<(&'a FiberID, &'a Fiber, f64)> {
     let mut min_utilization_fiber: Option<(&FiberID, &Fiber, f64)> = None;
 
-    for (fiber_id, fiber) in network.get_all_fibers().iter() {
+    for fiber in network.get_all_fibers().values() {
         let used_slots = fiber.count_used_slots();
         let total_slots = fiber.total_slots();
 
@@ -110,7 +207,7 @@ pub fn get_least_utilized_fiber<'a>(
         match min_utilization_fiber {
             Some((_, _, min_util)) if utilization >= min_util => {}
             _ => {
-                min_utilization_fiber = Some((fiber_id, fiber, utilization));
+                min_utilization_fiber = Some((&fiber.fiber_id, fiber, utilization));
             }
         }
     }
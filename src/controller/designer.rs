@@ -3,81 +3,47 @@ use rand::Rng;
 use crate::{
     config::Config,
     controller::expander::{self},
-    debugger, demand,
+    debugger, demand::{self, Demand},
     network::{Fiber, FiberID, Network, XCType},
     np_core::parameters::MAX_BYPASS_LEN,
-    topology::{get_fixed_shortest_path, get_random_shortest_path, get_shortet_paths, Topology},
+    topology::{get_fixed_shortest_path, get_random_shortest_path, get_shortet_paths, RouteCandidate, Topology},
     Edge, SD,
 };
 
 use super::{
-    ctrl_utils::{assign_all_paths, delete_all_paths},
+    checkpoint::{self, Checkpoint, CheckpointRef},
+    ctrl_utils::{assign_all_paths, assign_paths, delete_all_paths, delete_paths, get_affected_demand_indices},
     expander::get_min_expand_route_cand,
+    failure::{self, FailureResultsByKind},
     output,
 };
+pub(super) mod ilp;
 pub(super) mod iterative_designer;
+pub(super) mod local_search;
 
 pub(super) mod wxc_wbxc_designer;
-pub fn main(config: &Config, xc_types: &[XCType; 2]) -> (Network, Topology, String) {
+pub fn main(config: &Config, xc_types: &[XCType]) -> (Network, Topology, String) {
 
     // 出力ディレクトリの作成
     let output_dir: &str = &output::init_output_dir_wo_suffix(config);
 
-    // Config情報 + コネクションの保存
-    output::save_config(config, output_dir);
-    output::save_connection(output_dir);
-
     // トポロジの取得
     let topology = Topology::new(config);
 
-    // ネットワーク
-    let mut network = Network::new(config, &topology, xc_types);
-    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
-
-    // パス需要
-    let mut demand_list = demand::get_demand_list(config, &topology);
-
-    // パス割当 (WXC-based NWの作成)
-    assign_all_paths(config, &mut network, &topology, &mut demand_list);
-
-    // 従来手法における結果を記録
-    let conv_nw_w2w_fiber_count = *network
-        .get_fiber_breakdown()
-        .get(&[XCType::Wxc, XCType::Wxc])
-        .unwrap_or(&0);
-    println!("conv_nw_w2w_fiber_count:{:?}", conv_nw_w2w_fiber_count);
-    if *xc_types == [XCType::Wxc, XCType::Sxc] {
-        debugger::log_net_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
-    } else {
-        debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
-    }
-
-    output::save_conv_output(output_dir, &network, &demand_list);
-
-    // // println!("aaaaaaaaaaaaaaaaaa");
-
-    // let adjacency_file = r"C:\Users\kidanikouki\OneDrive - 国立大学法人東海国立大学機構\laboratory\layer_to_np-DAIKIBO\files\topology\jpn25.txt";
-
-    // // Python スクリプトを呼び出す
-    // // Python スクリプトの絶対パス
-    // let python_script = r"C:\Users\kidanikouki\OneDrive - 国立大学法人東海国立大学機構\laboratory\layer_to_np-DAIKIBO\src\controller\generate_graph.py";
-
-    // // Python スクリプトを呼び出す
-    // let output = Command::new("py")
-    //     .arg(python_script)
-    //     .arg(&adjacency_file)
-    //     .output()
-    //     .expect("Failed to execute Python script");
-
-    // if output.status.success() {
-    //     println!("Python script executed successfully.");
-    //     println!("Output: {}", String::from_utf8_lossy(&output.stdout));
-    // } else {
-    //     eprintln!("Python script failed.");
-    //     eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
-    // }
-
-    let mut taboo_list: Vec<SD> = vec![];
+    // 中断した設計を再開する場合は，チェックポイントから続ける
+    let Checkpoint {
+        bypass_len: first_bypass_len,
+        sds: mut resumed_sds,
+        mut network,
+        mut demand_list,
+        mut taboo_list,
+        mut all_installed_edges, // 🔧 バイパスで使ったエッジの履歴
+        conv_nw_w2w_fiber_count,
+        conv_failure_results,
+    } = match checkpoint::load(config, output_dir) {
+        Some(checkpoint) => checkpoint,
+        None => design_conventional(config, output_dir, &topology, xc_types),
+    };
 
     // loop {
     //     // まとめることのできるパスを探す
@@ -139,46 +105,58 @@ pub fn main(config: &Config, xc_types: &[XCType; 2]) -> (Network, Topology, Stri
     //     }
     // }
 
-    let mut all_installed_edges: Vec<Vec<Edge>> = vec![]; // 🔧 バイパスで使ったエッジの履歴
-
-    for bypass_len in 2..=MAX_BYPASS_LEN {
-        let mut sds = expander::find_emerge_sub_routes_sd_with_xc_types_with_len(
-            &network,
-            &demand_list,
-            &taboo_list,
-            xc_types,
-            bypass_len,
-        );
+    let mut attempt_count = 0;
+    for bypass_len in first_bypass_len..=MAX_BYPASS_LEN {
+        let mut sds = match resumed_sds.take() {
+            Some(sds) => sds,
+            None => expander::find_emerge_sub_routes_sd_count_with_xc_types_with_len(
+                &network,
+                &demand_list,
+                &taboo_list,
+                xc_types,
+                bypass_len,
+            ),
+        };
+        save_checkpoint(config, output_dir, bypass_len, &sds, &network, &demand_list, &taboo_list, &all_installed_edges, conv_nw_w2w_fiber_count, &conv_failure_results);
+
+        // 差分再割当では，バイパスを1区間ずつ設置して影響を受けるパス需要のみを再割当する
+        if config.network.incremental_reassignment {
+            let installed_edges = install_bypasses_incrementally(
+                config,
+                &mut network,
+                &mut demand_list,
+                &topology,
+                xc_types,
+                &sds,
+                &mut taboo_list,
+                &mut all_installed_edges,
+                conv_nw_w2w_fiber_count,
+            );
+            all_installed_edges.extend(installed_edges);
+            continue;
+        }
 
+        let baseline = AcceptanceBaseline::new(config, &network, &demand_list);
         loop {
             if sds.is_empty() {
                 println!("❌ sds が空になったため終了");
                 break;
             }
 
-            let mut working_network = network.clone();
+            // バイパスの設置と再割当は，不採用の場合に取り消せるようトランザクション中に行う
+            // 空ファイバの削除で追加されるタブーも，不採用の場合は取り消す
+            network.begin();
+            let taboo_count = taboo_list.len();
             let mut working_demand_list = demand_list.clone();
             let mut sd_fiber_changes: Vec<(SD, Vec<FiberID>, Vec<(Edge, XCType, XCType)>)> =
                 Vec::new();
 
-            delete_all_paths(&mut working_network, &mut working_demand_list);
+            delete_all_paths(&mut network, &mut working_demand_list);
 
             let mut installed_edges: Vec<Vec<Edge>> = vec![];
 
-            for sd in &sds {
-                let route_cand = if *xc_types == [XCType::Wxc, XCType::Sxc] {
-                    let route_cands = get_shortet_paths(&topology, sd, None);
-                    get_min_expand_route_cand(&working_network, &route_cands)
-                } else if config.network.fiber_unification {
-                    get_fixed_shortest_path(&topology, sd, None)
-                } else {
-                    get_random_shortest_path(
-                        &topology,
-                        sd,
-                        working_network.rng.gen_range(0..u64::MAX),
-                        None,
-                    )
-                };
+            for (sd, path_count) in &sds {
+                let route_cand = select_bypass_route(config, &topology, &mut network, xc_types, sd);
                 //println!("sd:{:?}",sd);
 
                 if route_cand.edge_route.len() <= 1 {
@@ -192,12 +170,14 @@ pub fn main(config: &Config, xc_types: &[XCType; 2]) -> (Network, Topology, Stri
                 installed_edges.push(target_edges.clone());
 
                 let removed_ids =
-                    expander::remove_fibers_by_edges(config, &mut working_network, &target_edges);
+                    expander::remove_fibers_by_edges(config, &mut network, &target_edges);
+                // 三層構成ではSDごとにバイパスを設置するレイヤを選ぶ
+                let bypass_xc_types = expander::select_bypass_xc_types(config, xc_types, *path_count);
                 let (_added_ids, added_info) = expander::expand_fibers_with_xc_types_install_edges(
                     config,
-                    &mut working_network,
+                    &mut network,
                     &target_edges,
-                    xc_types,
+                    &bypass_xc_types,
                     &mut all_installed_edges,
                 );
                 sd_fiber_changes.push((*sd, removed_ids, added_info));
@@ -205,61 +185,453 @@ pub fn main(config: &Config, xc_types: &[XCType; 2]) -> (Network, Topology, Stri
 
             assign_all_paths(
                 config,
-                &mut working_network,
+                &mut network,
                 &topology,
                 &mut working_demand_list,
             );
 
             // 空ファイバ削除
-            if xc_types.contains(&XCType::Fxc) || xc_types.contains(&XCType::Sxc) {
-                working_network.delete_empty_fibers_core(config, &mut taboo_list);
-            } else if xc_types.contains(&XCType::Wbxc) {
-                working_network.delete_empty_fibers_wb(config, &mut taboo_list);
-            } else {
-                unimplemented!();
-            }
+            delete_empty_fibers(config, &mut network, xc_types, &mut taboo_list);
 
             // ログ出力
-            if *xc_types == [XCType::Wxc, XCType::Sxc] {
-                debugger::log_net_analysis(
-                    config,
-                    &working_network,
-                    conv_nw_w2w_fiber_count,
-                    &working_demand_list,
-                );
-            } else {
-                debugger::log_analysis(
-                    config,
-                    &working_network,
-                    conv_nw_w2w_fiber_count,
-                    &working_demand_list,
-                );
-            }
+            log_analysis(config, &network, xc_types, conv_nw_w2w_fiber_count, &working_demand_list);
 
-            let count_ratio = debugger::analysis::calc_fiber_count_ratio(
-                &working_network,
-                conv_nw_w2w_fiber_count,
-            );
-
-            if count_ratio.is_finite()
-                && count_ratio <= 1.0 + config.network.fiber_increase_rate_limit
-            {
-                network = working_network;
+            if is_acceptable(config, &network, &working_demand_list, &baseline, conv_nw_w2w_fiber_count) {
+                network.commit();
                 demand_list = working_demand_list;
+                debugger::validate_network(config, &network, &demand_list);
 
                 all_installed_edges.extend(installed_edges);
                 //println!("all_installed_edges:{:?}", all_installed_edges);
                 sds.clear();
                 break;
             } else {
+                network.rollback();
+                taboo_list.truncate(taboo_count);
                 sds.pop();
+                debugger::validate_network(config, &network, &demand_list);
+
+                attempt_count += 1;
+                if checkpoint::is_due(config, attempt_count) {
+                    save_checkpoint(config, output_dir, bypass_len, &sds, &network, &demand_list, &taboo_list, &all_installed_edges, conv_nw_w2w_fiber_count, &conv_failure_results);
+                }
             }
         }
     }
+    // 再開時に設計をやり直さないよう，バイパス設置後の状態も保存する
+    save_checkpoint(config, output_dir, MAX_BYPASS_LEN + 1, &[], &network, &demand_list, &taboo_list, &all_installed_edges, conv_nw_w2w_fiber_count, &conv_failure_results);
     output::save_output(config, output_dir, &network, &demand_list);
     output::save_taboo_list(output_dir, &taboo_list);
+    let prop_failure_results = failure::run_failure_analysis(config, &format!("{output_dir}/prop/"), &network, &topology, &demand_list);
+    output::save_failure_summary(output_dir, &[("conv", &conv_failure_results), ("prop", &prop_failure_results)]);
 
     delete_all_paths(&mut network, &mut demand_list);
 
     (network, topology, output_dir.to_string())
 }
+
+/// 従来手法 (WXC-based NW) の設計と結果の記録を行い，バイパス設置前の状態を返す
+fn design_conventional(config: &Config, output_dir: &str, topology: &Topology, xc_types: &[XCType]) -> Checkpoint {
+
+    // Config情報 + コネクションの保存
+    output::save_config(config, output_dir);
+    output::save_connection(output_dir);
+
+    // ネットワーク
+    let mut network = Network::new(config, topology, xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+
+    // パス需要
+    let mut demand_list = demand::get_demand_list(config, topology);
+
+    // パス割当 (WXC-based NWの作成)
+    assign_all_paths(config, &mut network, topology, &mut demand_list);
+
+    // 従来手法における結果を記録
+    let conv_nw_w2w_fiber_count = *network
+        .get_fiber_breakdown()
+        .get(&[XCType::Wxc, XCType::Wxc])
+        .unwrap_or(&0);
+    println!("conv_nw_w2w_fiber_count:{:?}", conv_nw_w2w_fiber_count);
+    if *xc_types == [XCType::Wxc, XCType::Sxc] {
+        debugger::log_net_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+    } else {
+        debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+    }
+
+    output::save_conv_output(config, output_dir, &network, &demand_list);
+    if config.ilp.export {
+        if ilp::supports(xc_types) {
+            ilp::export_lp(config, topology, &demand_list, xc_types, &format!("{output_dir}/bypass.lp"));
+        } else {
+            eprintln!("[WARNING] ILP export supports fiber bypasses (FXC) only. Skipped for {:?}.", xc_types);
+        }
+    }
+    let conv_failure_results = failure::run_failure_analysis(config, &format!("{output_dir}/conv/"), &network, topology, &demand_list);
+
+    // // println!("aaaaaaaaaaaaaaaaaa");
+
+    // let adjacency_file = r"C:\Users\kidanikouki\OneDrive - 国立大学法人東海国立大学機構\laboratory\layer_to_np-DAIKIBO\files\topology\jpn25.txt";
+
+    // // Python スクリプトを呼び出す
+    // // Python スクリプトの絶対パス
+    // let python_script = r"C:\Users\kidanikouki\OneDrive - 国立大学法人東海国立大学機構\laboratory\layer_to_np-DAIKIBO\src\controller\generate_graph.py";
+
+    // // Python スクリプトを呼び出す
+    // let output = Command::new("py")
+    //     .arg(python_script)
+    //     .arg(&adjacency_file)
+    //     .output()
+    //     .expect("Failed to execute Python script");
+
+    // if output.status.success() {
+    //     println!("Python script executed successfully.");
+    //     println!("Output: {}", String::from_utf8_lossy(&output.stdout));
+    // } else {
+    //     eprintln!("Python script failed.");
+    //     eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
+    // }
+
+    Checkpoint {
+        bypass_len: 2,
+        sds: None,
+        network,
+        demand_list,
+        taboo_list: vec![],
+        all_installed_edges: vec![],
+        conv_nw_w2w_fiber_count,
+        conv_failure_results,
+    }
+}
+
+/// チェックポイントを書き出す
+#[allow(clippy::too_many_arguments)]
+fn save_checkpoint(
+    config: &Config,
+    output_dir: &str,
+    bypass_len: usize,
+    sds: &[(SD, usize)],
+    network: &Network,
+    demand_list: &[Demand],
+    taboo_list: &[SD],
+    all_installed_edges: &[Vec<Edge>],
+    conv_nw_w2w_fiber_count: usize,
+    conv_failure_results: &FailureResultsByKind,
+) {
+    let checkpoint = CheckpointRef {
+        bypass_len,
+        sds: Some(sds),
+        network,
+        demand_list,
+        taboo_list,
+        all_installed_edges,
+        conv_nw_w2w_fiber_count,
+        conv_failure_results,
+    };
+    checkpoint::save(config, output_dir, &checkpoint);
+}
+
+/// バイパスを設置する経路を選ぶ
+fn select_bypass_route(
+    config: &Config,
+    topology: &Topology,
+    network: &mut Network,
+    xc_types: &[XCType],
+    sd: &SD,
+) -> RouteCandidate {
+    if *xc_types == [XCType::Wxc, XCType::Sxc] {
+        let route_cands = get_shortet_paths(topology, sd, None);
+        get_min_expand_route_cand(network, &route_cands)
+    } else if config.network.fiber_unification {
+        get_fixed_shortest_path(topology, sd, None)
+    } else {
+        get_random_shortest_path(topology, sd, network.rng.gen_range(0..u64::MAX), None)
+    }
+}
+
+/// 空ファイバを削除する
+/// WBバイパスの撤去後に空ファイバを削除するため，三層構成もWBXC側で処理する
+fn delete_empty_fibers(config: &Config, network: &mut Network, xc_types: &[XCType], taboo_list: &mut Vec<SD>) {
+    if xc_types.contains(&XCType::Wbxc) {
+        network.delete_empty_fibers_wb(config, taboo_list);
+    } else if xc_types.contains(&XCType::Fxc) || xc_types.contains(&XCType::Sxc) {
+        network.delete_empty_fibers_core(config, taboo_list);
+    } else {
+        unimplemented!();
+    }
+}
+
+fn log_analysis(
+    config: &Config,
+    network: &Network,
+    xc_types: &[XCType],
+    conv_nw_w2w_fiber_count: usize,
+    demand_list: &[Demand],
+) {
+    if *xc_types == [XCType::Wxc, XCType::Sxc] {
+        debugger::log_net_analysis(config, network, conv_nw_w2w_fiber_count, demand_list);
+    } else {
+        debugger::log_analysis(config, network, conv_nw_w2w_fiber_count, demand_list);
+    }
+}
+
+/// バイパス設置前のNWの評価値 (採否の判定に用いる)
+struct AcceptanceBaseline {
+    total_cost: f64,
+    wss_cascaded_xc_count: usize,
+}
+
+impl AcceptanceBaseline {
+    fn new(config: &Config, network: &Network, demand_list: &[Demand]) -> Self {
+        AcceptanceBaseline {
+            total_cost: network.calc_total_cost(config, demand_list),
+            wss_cascaded_xc_count: network.count_wss_cascaded_xcs(config.network.wss_m),
+        }
+    }
+}
+
+/// バイパスを設置したNWを採用するかどうか
+fn is_acceptable(
+    config: &Config,
+    working_network: &Network,
+    working_demand_list: &[Demand],
+    baseline: &AcceptanceBaseline,
+    conv_nw_w2w_fiber_count: usize,
+) -> bool {
+    // FIBER: ファイバ数の増加率が上限以下，COST: 設備コストが減少する場合に採用する
+    let is_improved = match config.cost.criterion.to_uppercase().as_str() {
+        "FIBER" => {
+            let count_ratio = debugger::analysis::calc_fiber_count_ratio(working_network, conv_nw_w2w_fiber_count);
+            count_ratio.is_finite() && count_ratio <= 1.0 + config.network.fiber_increase_rate_limit
+        }
+        "COST" => working_network.calc_total_cost(config, working_demand_list) < baseline.total_cost,
+        _ => panic!("Invalid `criterion`: {}", config.cost.criterion),
+    };
+
+    // WSSの多段接続が必要なWXCを増やすバイパスは採用しない
+    is_improved && working_network.count_wss_cascaded_xcs(config.network.wss_m) <= baseline.wss_cascaded_xc_count
+}
+
+/// バイパスを`sds`の先頭から1区間ずつ設置し，その都度影響を受けるパス需要のみを再割当する
+///
+/// NWとパス需要を，採用条件を満たす最も多くのバイパスを設置した時点のものに更新し，設置した区間を返す
+/// (どの時点も採用条件を満たさなければ変更しない)．
+/// 全てのパスを削除して割り当て直し，不採用なら末尾のSDを外して設計し直す方法と比べ，
+/// 再割当するパス需要の数と設計のやり直しを大きく減らせる．
+/// パスの割当順が異なるため結果は完全には一致しないが，従来NWのWXC間ファイバ数に対するファイバ数の比の差は
+/// `fiber_increase_rate_limit`以下とする (`incremental_reassignment_test`で確認する)．
+#[allow(clippy::too_many_arguments)]
+fn install_bypasses_incrementally(
+    config: &Config,
+    network: &mut Network,
+    demand_list: &mut Vec<Demand>,
+    topology: &Topology,
+    xc_types: &[XCType],
+    sds: &[(SD, usize)],
+    taboo_list: &mut Vec<SD>,
+    all_installed_edges: &mut Vec<Vec<Edge>>,
+    conv_nw_w2w_fiber_count: usize,
+) -> Vec<Vec<Edge>> {
+    let baseline = AcceptanceBaseline::new(config, network, demand_list);
+    let mut working_demand_list = demand_list.clone();
+    let mut installed_edges: Vec<Vec<Edge>> = vec![];
+    let mut accepted_edges: Vec<Vec<Edge>> = vec![];
+    let mut accepted_taboo_count = taboo_list.len();
+
+    // 採用条件を満たした時点までの変更を確定し，それ以降の変更は最後に取り消す (タブーリストも同様)
+    network.begin();
+    for (sd, path_count) in sds {
+        let route_cand = select_bypass_route(config, topology, network, xc_types, sd);
+        if route_cand.edge_route.len() <= 1 {
+            continue;
+        }
+        let target_edges = route_cand.edge_route;
+
+        // 削除されるファイバを使用するパスと，バイパスを利用できるパスのみを削除する
+        let removing_ids = expander::find_fibers_to_remove(network, &target_edges);
+        let affected_indices = get_affected_demand_indices(network, &working_demand_list, &removing_ids, &target_edges);
+        delete_paths(network, &mut working_demand_list, &affected_indices);
+
+        expander::remove_fibers_by_edges(config, network, &target_edges);
+        let bypass_xc_types = expander::select_bypass_xc_types(config, xc_types, *path_count);
+        expander::expand_fibers_with_xc_types_install_edges(
+            config,
+            network,
+            &target_edges,
+            &bypass_xc_types,
+            all_installed_edges,
+        );
+        assign_paths(config, network, topology, &mut working_demand_list, &affected_indices);
+        installed_edges.push(target_edges);
+
+        delete_empty_fibers(config, network, xc_types, taboo_list);
+        log_analysis(config, network, xc_types, conv_nw_w2w_fiber_count, &working_demand_list);
+        debugger::validate_network(config, network, &working_demand_list);
+
+        if is_acceptable(config, network, &working_demand_list, &baseline, conv_nw_w2w_fiber_count) {
+            network.commit();
+            network.begin();
+            demand_list.clone_from(&working_demand_list);
+            accepted_edges.append(&mut installed_edges);
+            accepted_taboo_count = taboo_list.len();
+        }
+    }
+    network.rollback();
+    taboo_list.truncate(accepted_taboo_count);
+    debugger::validate_network(config, network, demand_list);
+
+    accepted_edges
+}
+
+#[test]
+fn incremental_reassignment_test() {
+    use std::fs;
+
+    let outdir = "target/incremental_reassignment_test";
+    let mut config = crate::config::test_config();
+    config.simulation.outdir = outdir.to_string();
+    let xc_types = [XCType::Wxc, XCType::Fxc];
+
+    for random_seed in 1..=3 {
+        config.simulation.random_seed = random_seed;
+
+        // 従来NWのWXC間ファイバ数
+        let topology = Topology::new(&config);
+        let mut network = Network::new(&config, &topology, &xc_types);
+        network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+        let mut demand_list = demand::get_demand_list(&config, &topology);
+        assign_all_paths(&config, &mut network, &topology, &mut demand_list);
+        let conv_nw_w2w_fiber_count = network.get_fiber_breakdown()[&[XCType::Wxc, XCType::Wxc]];
+
+        // 全再割当と差分再割当で設計し，ファイバ数の比を比べる
+        let count_ratios: Vec<f64> = [false, true]
+            .into_iter()
+            .map(|incremental_reassignment| {
+                config.network.incremental_reassignment = incremental_reassignment;
+                let _ = fs::remove_dir_all(outdir);
+                let (network, _topology, _output_dir) = main(&config, &xc_types);
+                debugger::analysis::calc_fiber_count_ratio(&network, conv_nw_w2w_fiber_count)
+            })
+            .collect();
+        let _ = fs::remove_dir_all(outdir);
+
+        for count_ratio in &count_ratios {
+            assert!(*count_ratio <= 1.0 + config.network.fiber_increase_rate_limit);
+        }
+        assert!(
+            (count_ratios[0] - count_ratios[1]).abs() <= config.network.fiber_increase_rate_limit,
+            "seed {}: {:?}", random_seed, count_ratios
+        );
+    }
+}
+
+#[test]
+fn install_bypasses_incrementally_test() {
+    let config = crate::config::test_config();
+    let xc_types = [XCType::Wxc, XCType::Fxc];
+    let topology = Topology::new(&config);
+    let mut network = Network::new(&config, &topology, &xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+    let mut demand_list = demand::get_demand_list(&config, &topology);
+    assign_all_paths(&config, &mut network, &topology, &mut demand_list);
+    let conv_nw_w2w_fiber_count = network.get_fiber_breakdown()[&[XCType::Wxc, XCType::Wxc]];
+
+    let conv_network = network.clone();
+    let conv_demand_list = demand_list.clone();
+    let sds = expander::find_emerge_sub_routes_sd_count_with_xc_types_with_len(&network, &demand_list, &[], &xc_types, 2);
+    let mut taboo_list = vec![];
+    let installed_edges = install_bypasses_incrementally(
+        &config,
+        &mut network,
+        &mut demand_list,
+        &topology,
+        &xc_types,
+        &sds[..1],
+        &mut taboo_list,
+        &mut vec![],
+        conv_nw_w2w_fiber_count,
+    );
+    assert_eq!(installed_edges.len(), 1);
+
+    // 撤去されたファイバを使用していたパスと，バイパスを利用できるパスのみが再割当される
+    let mut rerouted_count = 0;
+    for (conv_demand, demand) in conv_demand_list.iter().zip(&demand_list) {
+        if conv_demand.fiber_ids == demand.fiber_ids && conv_demand.slot_heads == demand.slot_heads {
+            continue;
+        }
+        rerouted_count += 1;
+
+        let uses_removed_fiber = conv_demand.get_fiber_routes()
+            .into_iter()
+            .flatten()
+            .any(|fiber_id| !network.get_fibers().contains_key(fiber_id));
+        let edge_route: Vec<Edge> = conv_demand.fiber_ids
+            .iter()
+            .map(|fiber_id| conv_network.get_fiber_by_id(fiber_id).edge)
+            .collect();
+        let can_use_bypass = crate::utils::contains_subslice(&edge_route, &installed_edges[0]);
+        assert!(uses_removed_fiber || can_use_bypass, "demand {} is rerouted", demand.index);
+    }
+    assert!(0 < rerouted_count && rerouted_count < demand_list.len());
+}
+
+#[test]
+fn added_wxc_fxc_design_test() {
+    use std::fs;
+    use crate::network::EdgesType;
+
+    let outdir = "target/added_wxc_fxc_design_test";
+    let mut config = crate::config::test_config();
+    config.simulation.outdir = outdir.to_string();
+    config.network.node_configuration = "ADDED_WXC-FXC".to_string();
+    let xc_types = super::parse_node_configuration(&config.network.node_configuration);
+    assert_eq!(xc_types, [XCType::Wxc, XCType::Added_Wxc, XCType::Fxc]);
+
+    // 設計から出力 (WXC通過回数，ネットワーク情報) まで通す
+    let _ = fs::remove_dir_all(outdir);
+    let (network, _topology, _output_dir) = main(&config, xc_types);
+    let _ = fs::remove_dir_all(outdir);
+    let fxc_bypass_count = network.get_fiber_breakdown().get(&[XCType::Added_Wxc, XCType::Fxc]).copied().unwrap_or(0);
+    let exported_fxc_bypass_count: usize = network.export()
+        .iter()
+        .filter(|((edges_type, _edges), _count)| *edges_type == EdgesType::Fxc)
+        .map(|(_key, count)| count)
+        .sum();
+    assert!(fxc_bypass_count > 0);
+    assert_eq!(exported_fxc_bypass_count, fxc_bypass_count);
+
+    // 補助WXCを入口とするバイパスを1本設置し，WXC通過回数とexportを確かめる
+    let topology = Topology::new(&config);
+    let mut network = Network::new(&config, &topology, xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+    let mut demand_list = demand::get_demand_list(&config, &topology);
+    assign_all_paths(&config, &mut network, &topology, &mut demand_list);
+    let conv_nw_w2w_fiber_count = network.get_fiber_breakdown()[&[XCType::Wxc, XCType::Wxc]];
+
+    let sds = expander::find_emerge_sub_routes_sd_count_with_xc_types_with_len(&network, &demand_list, &[], xc_types, 2);
+    let installed_edges = install_bypasses_incrementally(
+        &config,
+        &mut network,
+        &mut demand_list,
+        &topology,
+        xc_types,
+        &sds[..1],
+        &mut vec![],
+        &mut vec![],
+        conv_nw_w2w_fiber_count,
+    );
+    assert_eq!(installed_edges.len(), 1);
+    assert_eq!(network.get_fiber_breakdown()[&[XCType::Added_Wxc, XCType::Fxc]], 1);
+
+    // 補助WXCのポートもWXCのポートとして数えるため，バイパスを通るパス需要も含めて全てが1回以上WXCを通過する
+    let wxc_pass_count = output::calc_wxc_pass_count(&network, &demand_list);
+    assert_eq!(wxc_pass_count.iter().sum::<usize>(), demand_list.len());
+    let bypass_users = demand_list
+        .iter()
+        .filter(|demand| demand.fiber_ids.iter().any(|fiber_id| network.get_fiber_sd_xc_type_by_id(fiber_id)[0] == XCType::Added_Wxc))
+        .count();
+    assert!(bypass_users > 0);
+
+    let export = network.export();
+    assert_eq!(export[&(EdgesType::Fxc, installed_edges[0].clone())], 1);
+}
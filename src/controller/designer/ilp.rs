@@ -0,0 +1,435 @@
+use std::{fs::File, io::Write};
+
+use fxhash::FxHashMap;
+
+use crate::{
+    config::Config,
+    controller::{ctrl_utils::{assign_all_paths, delete_all_paths}, expander, output},
+    debugger,
+    demand::{self, Demand},
+    network::{Network, XCType},
+    np_core::parameters::{MAX_BYPASS_LEN, MIN_BYPASS_LEN, SLOT},
+    topology::{get_fixed_shortest_path, Topology},
+    utils::{contains_subslice, enumerate_subsequences, read_file},
+    Edge, SD,
+};
+
+/// LPファイルの1行あたりの項数
+const TERMS_PER_LINE: usize = 8;
+
+/// ソルバの解 (エッジごとのWXC間ファイバ数，経路ごとのバイパス本数)
+type Solution = (Vec<(Edge, usize)>, Vec<(Vec<Edge>, usize)>);
+
+/// バイパス配置問題
+///
+/// 各Demandの経路は固定最短経路とし，経路ごとにパス数をまとめる．
+/// 波長連続性制約は考慮しない (緩和問題) ため，解の読込後にパスを割り当て直すと
+/// ファイバが追加される場合がある．
+struct BypassProblem {
+    /// 物理トポロジのエッジ
+    edges: Vec<Edge>,
+    /// 経路とその経路を通るパス数
+    route_groups: Vec<(Vec<Edge>, usize)>,
+    /// バイパス候補
+    candidates: Vec<Vec<Edge>>,
+    /// 経路ごとの，その経路に含まれるバイパス候補のインデックス
+    candidates_on_route: Vec<Vec<usize>>,
+    /// バイパスの入口ポートをWXCに置くかどうか (補助WXCを用いる構成では置かない)
+    entry_on_wxc: bool,
+    /// バイパスを設置しない場合に必要なファイバ数
+    conv_fiber_count: usize,
+}
+
+/// ILPが対応するノード構成か (ファイババイパスのみ)
+pub fn supports(xc_types: &[XCType]) -> bool {
+    matches!(xc_types, [XCType::Wxc, XCType::Fxc] | [XCType::Wxc, XCType::Added_Wxc, XCType::Fxc])
+}
+
+impl BypassProblem {
+    fn new(topology: &Topology, demand_list: &[Demand], xc_types: &[XCType]) -> Self {
+        let entry_on_wxc = match xc_types {
+            [XCType::Wxc, XCType::Fxc] => true,
+            [XCType::Wxc, XCType::Added_Wxc, XCType::Fxc] => false,
+            _ => unreachable!("ILP supports fiber bypasses (FXC) only: {:?}", xc_types),
+        };
+
+        let mut route_counter: FxHashMap<Vec<Edge>, usize> = FxHashMap::default();
+        for demand in demand_list {
+            let route = get_fixed_shortest_path(topology, &demand.sd, None).edge_route;
+            *route_counter.entry(route).or_insert(0) += 1;
+        }
+        let mut route_groups: Vec<(Vec<Edge>, usize)> = route_counter.into_iter().collect();
+        route_groups.sort();
+
+        let mut candidates: Vec<Vec<Edge>> = route_groups
+            .iter()
+            .flat_map(|(route, _)| enumerate_subsequences(route, MIN_BYPASS_LEN, Some(MAX_BYPASS_LEN)))
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+
+        let candidates_on_route = route_groups
+            .iter()
+            .map(|(route, _)| {
+                (0..candidates.len())
+                    .filter(|&index| contains_subslice(route, &candidates[index]))
+                    .collect()
+            })
+            .collect();
+
+        let mut problem = Self {
+            edges: topology.edges.clone(),
+            route_groups,
+            candidates,
+            candidates_on_route,
+            entry_on_wxc,
+            conv_fiber_count: 0,
+        };
+        problem.conv_fiber_count = problem.edges.iter().map(|edge| problem.load(edge).div_ceil(SLOT)).sum();
+
+        problem
+    }
+
+    /// エッジを通るパス数
+    fn load(&self, edge: &Edge) -> usize {
+        self.route_groups
+            .iter()
+            .filter(|(route, _)| route.contains(edge))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+fn x_name(edge: &Edge) -> String {
+    format!("x_{}_{}", usize::from(edge.src), usize::from(edge.dst))
+}
+
+fn y_name(route: &[Edge]) -> String {
+    let mut name = format!("y_{}", usize::from(route[0].src));
+    for edge in route {
+        name.push_str(&format!("_{}", usize::from(edge.dst)));
+    }
+    name
+}
+
+fn z_name(route_index: usize, candidate_index: usize) -> String {
+    format!("z_{route_index}_{candidate_index}")
+}
+
+fn write_linear_expr(f: &mut File, terms: &[(i64, String)]) {
+    for (i, (coef, name)) in terms.iter().enumerate() {
+        if i > 0 && i % TERMS_PER_LINE == 0 {
+            write!(f, "\n   ").unwrap();
+        }
+        let sign = if *coef < 0 { "- " } else if i == 0 { "" } else { "+ " };
+        write!(f, " {}{} {}", sign, coef.abs(), name).unwrap();
+    }
+}
+
+fn write_constraint(f: &mut File, name: &str, terms: &[(i64, String)], sense: &str, rhs: i64) {
+    if terms.is_empty() {
+        return;
+    }
+    write!(f, " {name}:").unwrap();
+    write_linear_expr(f, terms);
+    writeln!(f, " {sense} {rhs}").unwrap();
+}
+
+/// バイパス配置問題をLP形式 (CPLEX LP) で出力する
+///
+/// 変数はWXC間ファイバ数`x_{src}_{dst}`，バイパス本数`y_{n0}_{n1}_..._{nk}`，
+/// 経路rのパスのうちバイパスbに収容する数`z_{r}_{b}`．
+/// ファイバ数の上限 (バイパスなしの`1 + fiber_increase_rate_limit`倍) とWXCの入出力ポート数の上限 (`wss_m`) の下で，
+/// WXCのポート数の総和を最小化する．
+pub fn export_lp(config: &Config, topology: &Topology, demand_list: &[Demand], xc_types: &[XCType], filepath: &str) {
+    let problem = BypassProblem::new(topology, demand_list, xc_types);
+    let slot = SLOT as i64;
+
+    let mut f = File::create(filepath).unwrap_or_else(|_| panic!("ファイルの作成に失敗しました: {filepath}"));
+    writeln!(f, "\\ Bypass placement problem").unwrap();
+    writeln!(f, "\\ topology: {}, node_configuration: {}", config.network.topology, config.network.node_configuration).unwrap();
+    writeln!(f, "\\ demands: {}, routes: {}, candidates: {}, conv_fiber_count: {}",
+        demand_list.len(), problem.route_groups.len(), problem.candidates.len(), problem.conv_fiber_count).unwrap();
+
+    // 目的関数: WXCのポート数
+    let entry_port = if problem.entry_on_wxc { 2 } else { 1 };
+    let mut objective: Vec<(i64, String)> = problem.edges.iter().map(|edge| (2, x_name(edge))).collect();
+    objective.extend(problem.candidates.iter().map(|candidate| (entry_port, y_name(candidate))));
+    writeln!(f, "Minimize").unwrap();
+    write!(f, " wxc_ports:").unwrap();
+    write_linear_expr(&mut f, &objective);
+    writeln!(f).unwrap();
+
+    writeln!(f, "Subject To").unwrap();
+
+    // 各エッジのパスは，WXC間ファイバかバイパスで収容する
+    for edge in &problem.edges {
+        let load = problem.load(edge);
+        if load == 0 {
+            continue;
+        }
+        let mut terms = vec![(slot, x_name(edge))];
+        for (route_index, candidate_indices) in problem.candidates_on_route.iter().enumerate() {
+            for &candidate_index in candidate_indices {
+                if problem.candidates[candidate_index].contains(edge) {
+                    terms.push((1, z_name(route_index, candidate_index)));
+                }
+            }
+        }
+        write_constraint(&mut f, &format!("load_{}", &x_name(edge)[2..]), &terms, ">=", load as i64);
+    }
+
+    // バイパスの容量
+    for (candidate_index, candidate) in problem.candidates.iter().enumerate() {
+        let mut terms = vec![(slot, y_name(candidate))];
+        for (route_index, candidate_indices) in problem.candidates_on_route.iter().enumerate() {
+            if candidate_indices.contains(&candidate_index) {
+                terms.push((-1, z_name(route_index, candidate_index)));
+            }
+        }
+        write_constraint(&mut f, &format!("cap_{candidate_index}"), &terms, ">=", 0);
+    }
+
+    // 各パスは，各エッジで高々一つのバイパスに収容される
+    for (route_index, (route, count)) in problem.route_groups.iter().enumerate() {
+        for edge in route {
+            let terms: Vec<(i64, String)> = problem.candidates_on_route[route_index]
+                .iter()
+                .filter(|&&candidate_index| problem.candidates[candidate_index].contains(edge))
+                .map(|&candidate_index| (1, z_name(route_index, candidate_index)))
+                .collect();
+            write_constraint(&mut f, &format!("cover_{route_index}_{}", &x_name(edge)[2..]), &terms, "<=", *count as i64);
+        }
+    }
+
+    // WXCの入出力ポート数
+    for node in 0..topology.link_matrix.len() {
+        let mut input_terms: Vec<(i64, String)> = problem.edges
+            .iter()
+            .filter(|edge| usize::from(edge.dst) == node)
+            .map(|edge| (1, x_name(edge)))
+            .collect();
+        input_terms.extend(problem.candidates
+            .iter()
+            .filter(|candidate| usize::from(candidate.last().unwrap().dst) == node)
+            .map(|candidate| (1, y_name(candidate))));
+        write_constraint(&mut f, &format!("wxc_in_{node}"), &input_terms, "<=", config.network.wss_m as i64);
+
+        let mut output_terms: Vec<(i64, String)> = problem.edges
+            .iter()
+            .filter(|edge| usize::from(edge.src) == node)
+            .map(|edge| (1, x_name(edge)))
+            .collect();
+        if problem.entry_on_wxc {
+            output_terms.extend(problem.candidates
+                .iter()
+                .filter(|candidate| usize::from(candidate[0].src) == node)
+                .map(|candidate| (1, y_name(candidate))));
+        }
+        write_constraint(&mut f, &format!("wxc_out_{node}"), &output_terms, "<=", config.network.wss_m as i64);
+    }
+
+    // ファイバ数の上限
+    let fiber_limit = (problem.conv_fiber_count as f64 * (1.0 + config.network.fiber_increase_rate_limit)).floor() as i64;
+    let mut fiber_terms: Vec<(i64, String)> = problem.edges.iter().map(|edge| (1, x_name(edge))).collect();
+    fiber_terms.extend(problem.candidates.iter().map(|candidate| (candidate.len() as i64, y_name(candidate))));
+    write_constraint(&mut f, "fiber_limit", &fiber_terms, "<=", fiber_limit);
+
+    // 整数変数
+    writeln!(f, "General").unwrap();
+    let mut names: Vec<String> = problem.edges.iter().map(x_name).collect();
+    names.extend(problem.candidates.iter().map(|candidate| y_name(candidate)));
+    for (route_index, candidate_indices) in problem.candidates_on_route.iter().enumerate() {
+        names.extend(candidate_indices.iter().map(|&candidate_index| z_name(route_index, candidate_index)));
+    }
+    for chunk in names.chunks(TERMS_PER_LINE) {
+        writeln!(f, " {}", chunk.join(" ")).unwrap();
+    }
+    writeln!(f, "End").unwrap();
+}
+
+/// 変数名からノード列を取得する
+fn parse_var_nodes(name: &str) -> Option<Vec<usize>> {
+    name[2..].split('_').map(|x| x.parse().ok()).collect()
+}
+
+/// ソルバの解ファイルの内容から，WXC間ファイバ数とバイパス本数を読み込む
+///
+/// 各行の`x_`または`y_`で始まる語を変数名，その次の語を値とみなす．
+/// CBC，HiGHS，Gurobi (`.sol`) の出力形式を読み込める．
+fn parse_solution(contents: &str) -> Solution {
+    let mut fiber_counts = vec![];
+    let mut bypass_counts = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with('\\') {
+            continue;
+        }
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some(position) = tokens.iter().position(|token| token.starts_with("x_") || token.starts_with("y_")) else {
+            continue;
+        };
+        let Some(value) = tokens.get(position + 1).and_then(|token| token.parse::<f64>().ok()) else {
+            continue;
+        };
+        let count = value.round() as usize;
+        if count == 0 {
+            continue;
+        }
+
+        let name = tokens[position];
+        let Some(nodes) = parse_var_nodes(name) else {
+            panic!("Invalid variable name: {name}");
+        };
+        let edges: Vec<Edge> = nodes.windows(2).map(|pair| Edge::new(pair[0], pair[1])).collect();
+        if name.starts_with("x_") && edges.len() == 1 {
+            fiber_counts.push((edges[0], count));
+        } else if name.starts_with("y_") && edges.len() >= MIN_BYPASS_LEN {
+            bypass_counts.push((edges, count));
+        } else {
+            panic!("Invalid variable name: {name}");
+        }
+    }
+
+    fiber_counts.sort();
+    bypass_counts.sort();
+    (fiber_counts, bypass_counts)
+}
+
+/// ソルバの解ファイルを読み込み，WXC間ファイバとバイパスを設置したネットワークを作成する
+pub fn import_solution(config: &Config, topology: &Topology, xc_types: &[XCType], filepath: &str) -> Network {
+    let contents = read_file(filepath).unwrap_or_else(|_| panic!("ファイルの読込に失敗しました: {filepath}"));
+    let (fiber_counts, bypass_counts) = parse_solution(&contents);
+
+    // `Network::new`は各エッジにWXC間ファイバを1本設置するため，解のファイバ数に合わせて増減する
+    let mut network = Network::new(config, topology, xc_types);
+    let fiber_counts: FxHashMap<Edge, usize> = fiber_counts.into_iter().collect();
+    for edge in &topology.edges {
+        let count = fiber_counts.get(edge).copied().unwrap_or(0);
+        if count == 0 {
+            for fiber_id in network.get_fiber_id_on_edge(edge) {
+                network.delete_fiber(config, &fiber_id);
+            }
+        }
+        for _ in 1..count {
+            let fiber = expander::generate_new_fiber(&mut network, edge, XCType::Wxc, XCType::Wxc);
+            network.regist_fiber(fiber);
+        }
+    }
+    for (route, count) in bypass_counts {
+        for _ in 0..count {
+            expander::expand_fibers_with_xc_types_install_edges(config, &mut network, &route, xc_types, &mut vec![]);
+        }
+    }
+
+    network
+}
+
+/// ILPによる設計
+///
+/// 従来NWを作成した後，`solution_filepath`が空であればILPを出力して終了する．
+/// 指定されていれば解を読み込み，パスを割り当て直した結果を提案手法の結果として出力する．
+pub fn main(config: &Config, xc_types: &[XCType]) -> (Network, Topology, String) {
+    assert!(supports(xc_types), "ILP supports fiber bypasses (FXC) only: {:?}", xc_types);
+
+    let output_dir: &str = &output::init_output_dir_wo_suffix(config);
+    output::save_config(config, output_dir);
+    output::save_connection(output_dir);
+
+    let topology = Topology::new(config);
+
+    // 従来NW
+    let mut network = Network::new(config, &topology, xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+    let mut demand_list = demand::get_demand_list(config, &topology);
+    assign_all_paths(config, &mut network, &topology, &mut demand_list);
+
+    let conv_nw_w2w_fiber_count = *network
+        .get_fiber_breakdown()
+        .get(&[XCType::Wxc, XCType::Wxc])
+        .unwrap_or(&0);
+    debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+    output::save_conv_output(config, output_dir, &network, &demand_list);
+
+    if config.ilp.solution_filepath.is_empty() {
+        let filepath = format!("{output_dir}/bypass.lp");
+        export_lp(config, &topology, &demand_list, xc_types, &filepath);
+        println!("ILP: {filepath}");
+    } else {
+        delete_all_paths(&mut network, &mut demand_list);
+
+        network = import_solution(config, &topology, xc_types, &config.ilp.solution_filepath);
+        network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+        assign_all_paths(config, &mut network, &topology, &mut demand_list);
+
+        let mut taboo_list: Vec<SD> = vec![];
+        network.delete_empty_fibers_core(config, &mut taboo_list);
+
+        debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+        output::save_output(config, output_dir, &network, &demand_list);
+    }
+
+    delete_all_paths(&mut network, &mut demand_list);
+
+    (network, topology, output_dir.to_string())
+}
+
+#[test]
+fn parse_solution_test() {
+    let contents = "\
+Optimal - objective value 10.00000000
+      0 x_0_1                     2                       2
+      1 x_1_0                     0                       2
+      2 y_0_1_2                   1                       1
+# comment x_3_4 5
+y_2_1_0 1.0000000001
+";
+    let (fiber_counts, bypass_counts) = parse_solution(contents);
+
+    assert_eq!(fiber_counts, vec![(Edge::new(0, 1), 2)]);
+    assert_eq!(bypass_counts, vec![
+        (vec![Edge::new(0, 1), Edge::new(1, 2)], 1),
+        (vec![Edge::new(2, 1), Edge::new(1, 0)], 1),
+    ]);
+    assert_eq!(y_name(&[Edge::new(0, 1), Edge::new(1, 2)]), "y_0_1_2");
+}
+
+#[test]
+fn import_solution_test() {
+    use std::fs;
+
+    let config = crate::config::test_config();
+    let topology = Topology::new(&config);
+    let demand_list = demand::get_demand_list(&config, &topology);
+    let xc_types = [XCType::Wxc, XCType::Fxc];
+
+    let lp_filepath = "target/import_solution_test.lp";
+    export_lp(&config, &topology, &demand_list, &xc_types, lp_filepath);
+    let lp = fs::read_to_string(lp_filepath).unwrap();
+
+    // 出力したLPの変数を用いて解を書く (先頭のエッジはファイバなし，他は2本，長さ2のバイパスを1本)
+    let bypass_name = lp
+        .split_whitespace()
+        .find(|token| token.starts_with("y_") && parse_var_nodes(token).unwrap().len() == MIN_BYPASS_LEN + 1)
+        .unwrap();
+    let mut solution = String::new();
+    for (index, edge) in topology.edges.iter().enumerate() {
+        assert!(lp.contains(&x_name(edge)));
+        solution.push_str(&format!("{} {}\n", x_name(edge), if index == 0 { 0 } else { 2 }));
+    }
+    solution.push_str(&format!("{bypass_name} 1\n"));
+
+    let solution_filepath = "target/import_solution_test.sol";
+    fs::write(solution_filepath, solution).unwrap();
+    let network = import_solution(&config, &topology, &xc_types, solution_filepath);
+    let _ = fs::remove_file(lp_filepath);
+    let _ = fs::remove_file(solution_filepath);
+
+    let fiber_breakdown = network.get_fiber_breakdown();
+    assert_eq!(fiber_breakdown[&[XCType::Wxc, XCType::Wxc]], 2 * (topology.edges.len() - 1));
+    assert_eq!(fiber_breakdown[&[XCType::Wxc, XCType::Fxc]], 1);
+    assert_eq!(fiber_breakdown[&[XCType::Fxc, XCType::Wxc]], 1);
+    assert!(network.get_fiber_id_on_edge(&topology.edges[0]).is_empty());
+}
@@ -1,7 +1,8 @@
 use rand_chacha::ChaCha8Rng;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 
 use crate::{
-    config::Config, controller::designer::main, network::{Network, XCType}, topology::Topology
+    config::Config, controller::designer::main, network::{Network, XCType}, topology::Topology, utils
 };
 use rand::Rng;
 
@@ -10,32 +11,38 @@ mod best;
 
 pub use average::main as average_main;
 pub use best::main as best_main;
+pub use best::SeedScore;
 
+/// `n`個のシードで設計を行う
+/// シードは`rng`から順に生成するため，並列に実行しても結果の順序と内容は変わらない
 pub fn get_results(
     rng: &mut ChaCha8Rng,
     n: usize,
     config: &Config,
-    xc_types: &[XCType; 2]
-) -> Vec<(Network, Topology, String)> {
-    let mut results = vec![];
-
-    for i in 0..n {
-        let new_seed = rng.gen_range(0..i64::MAX as u64);
-        println!("\n\nNEW SEED: {}", new_seed);
-
-        let mut new_config = config.clone();
-        new_config.simulation.random_seed = new_seed;
-        new_config.simulation.outdir = format!(
-            "{}/{:02}_{:010}",
-            config.simulation.outdir,
-            i,
-            new_seed
-        );
-
-        let (network, topology, output_dir) = main(&new_config, xc_types);
-
-        results.push((network, topology, output_dir));
-    }
-
-    results
+    xc_types: &[XCType]
+) -> Vec<(u64, Network, Topology, String)> {
+    let seeds: Vec<u64> = (0..n).map(|_| rng.gen_range(0..i64::MAX as u64)).collect();
+
+    utils::thread_pool().install(|| {
+        seeds
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, new_seed)| {
+                println!("\n\nNEW SEED: {}", new_seed);
+
+                let mut new_config = config.clone();
+                new_config.simulation.random_seed = new_seed;
+                new_config.simulation.outdir = format!(
+                    "{}/{:02}_{:010}",
+                    config.simulation.outdir,
+                    i,
+                    new_seed
+                );
+
+                let (network, topology, output_dir) = main(&new_config, xc_types);
+
+                (new_seed, network, topology, output_dir)
+            })
+            .collect()
+    })
 }
@@ -1,42 +1,117 @@
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
     config::Config,
-    controller::{ designer::iterative_designer::get_results, output::{self} },
+    controller::{ ctrl_utils::assign_all_paths, designer::iterative_designer::get_results, output::{self} },
+    debugger::analysis,
+    demand,
     network::{ self, Network, XCType },
     np_core::parameters::MEAN_N,
     topology::Topology,
+    utils,
 };
 
-pub fn main(config: &Config, xc_types: &[XCType; 2]) -> (Network, Topology, String) {
+/// シードごとの設計の評価指標
+#[derive(Debug, Clone)]
+pub struct SeedScore {
+    pub index: usize,
+    pub seed: u64,
+    pub output_dir: String,
+    /// W-W以外のファイバの割合
+    pub fiber_ratio: f64,
+    pub max_wxc_size: usize,
+    pub wxc_pass: f64,
+    pub cost: f64,
+    /// 選択基準の値 (小さいほど良い)
+    pub score: f64,
+}
+
+/// 設計したNWにパス需要を割り当て直して評価する
+/// 設計後のNWはパスを削除してあるため，シードからパス需要を作り直す
+fn evaluate(config: &Config, index: usize, seed: u64, network: &Network, topology: &Topology, output_dir: &str) -> SeedScore {
+    let fiber_breakdown = network.get_fiber_breakdown();
+    let sum: usize = fiber_breakdown.values().sum();
+    let fxc_related = sum - fiber_breakdown.get(&[XCType::Wxc, XCType::Wxc]).unwrap_or(&0);
+
+    let mut seed_config = config.clone();
+    seed_config.simulation.random_seed = seed;
+    let mut demand_list = demand::get_demand_list(&seed_config, topology);
+    let mut network = network.clone();
+    assign_all_paths(&seed_config, &mut network, topology, &mut demand_list);
+
+    SeedScore {
+        index,
+        seed,
+        output_dir: output_dir.to_string(),
+        fiber_ratio: (fxc_related as f64) / (sum as f64),
+        max_wxc_size: analysis::calc_max_xc_size(&network, &XCType::Wxc),
+        wxc_pass: output::calc_wxc_pass_count_average(&network, &demand_list),
+        cost: network.calc_total_cost(config, &demand_list),
+        score: 0.0,
+    }
+}
+
+/// 選択基準の値を設定する
+/// WEIGHTEDの場合は，各指標をシード間の平均で正規化して重み付き和をとる
+fn set_scores(config: &Config, scores: &mut [SeedScore]) {
+    let n = scores.len() as f64;
+    let mean_ww_ratio = scores.iter().map(|s| 1.0 - s.fiber_ratio).sum::<f64>() / n;
+    let mean_max_wxc_size = scores.iter().map(|s| s.max_wxc_size as f64).sum::<f64>() / n;
+    let mean_wxc_pass = scores.iter().map(|s| s.wxc_pass).sum::<f64>() / n;
+    let mean_cost = scores.iter().map(|s| s.cost).sum::<f64>() / n;
+
+    let best = &config.best;
+    for s in scores.iter_mut() {
+        s.score = match best.objective.to_uppercase().as_str() {
+            "FIBER_RATIO" => -s.fiber_ratio,
+            "MAX_WXC_SIZE" => s.max_wxc_size as f64,
+            "WXC_PASS" => s.wxc_pass,
+            "COST" => s.cost,
+            "WEIGHTED" => {
+                best.weight_fiber_ratio * (1.0 - s.fiber_ratio) / mean_ww_ratio
+                    + best.weight_max_wxc_size * s.max_wxc_size as f64 / mean_max_wxc_size
+                    + best.weight_wxc_pass * s.wxc_pass / mean_wxc_pass
+                    + best.weight_cost * s.cost / mean_cost
+            }
+            _ => panic!("Invalid `objective`: {}", best.objective),
+        };
+    }
+}
+
+pub fn main(config: &Config, xc_types: &[XCType]) -> (Network, Topology, String) {
 
     output::save_config(config, &config.simulation.outdir);
 
     let mut rng = ChaCha8Rng::seed_from_u64(config.simulation.random_seed);
     let results = get_results(&mut rng, MEAN_N, config, xc_types);
 
-    let mut best_score = (0, 0.0);
-    for (index, (network, _topology, _)) in results.iter().enumerate() {
-        let fiber_breakdown = network.get_fiber_breakdown();
-        let sum: usize = fiber_breakdown.values().sum();
-        let fxc_related = sum - fiber_breakdown.get(&[XCType::Wxc, XCType::Wxc]).unwrap_or(&0);
-        let score = (fxc_related as f64) / (sum as f64);
-        // similar function exists
-
-        if best_score.1 < score {
-            best_score = (index, score);
-        }
-    }
+    let mut scores: Vec<SeedScore> = utils::thread_pool().install(|| {
+        results
+            .par_iter()
+            .enumerate()
+            .map(|(index, (seed, network, topology, output_dir))| evaluate(config, index, *seed, network, topology, output_dir))
+            .collect()
+    });
+    set_scores(config, &mut scores);
+
+    // 同点の場合はインデックスの小さい方を選ぶ
+    let best_index = scores
+        .iter()
+        .filter(|s| !s.score.is_nan())
+        .min_by(|a, b| a.score.total_cmp(&b.score).then(a.index.cmp(&b.index)))
+        .map_or(0, |s| s.index);
 
-    let best_outdir = results[best_score.0].2.clone();
-    output::save_best(&config.simulation.outdir, &format!("{}", best_score.0));
+    let best_outdir = results[best_index].3.clone();
+    output::save_best(&config.simulation.outdir, &format!("{}", best_index));
+    output::save_best_ranking(&config.simulation.outdir, &scores);
 
     let topology = Topology::new(config);
     let network = network::network_from_hashmap(
         config,
         &topology,
-        results[best_score.0].0.export()
+        results[best_index].1.export()
     );
 
     (network, topology, best_outdir)
@@ -0,0 +1,325 @@
+use std::{fmt::Display, time::Instant};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    config::Config,
+    controller::{ctrl_utils::{assign_all_paths, delete_all_paths}, expander, output},
+    debugger::{self, analysis},
+    demand::{self, Demand},
+    network::{Network, XCType},
+    np_core::parameters::{MAX_BYPASS_LEN, MIN_BYPASS_LEN},
+    topology::{get_fixed_shortest_path, get_random_shortest_path, Topology},
+    Edge, SD,
+};
+
+/// 局所探索の近傍操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMove {
+    /// 貪欲法による初期解
+    Greedy,
+    /// バイパスを追加する
+    Add,
+    /// バイパスを撤去する
+    Remove,
+    /// バイパスを撤去し，別の区間に設置する
+    Move,
+}
+
+impl Display for SearchMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchMove::Greedy => write!(f, "GREEDY"),
+            SearchMove::Add => write!(f, "ADD"),
+            SearchMove::Remove => write!(f, "REMOVE"),
+            SearchMove::Move => write!(f, "MOVE"),
+        }
+    }
+}
+
+/// 探索の1反復の記録
+#[derive(Debug, Clone, Copy)]
+pub struct SearchStep {
+    pub iteration: usize,
+    pub search_move: SearchMove,
+    /// 近傍解のバイパス数
+    pub bypass_count: usize,
+    /// 近傍解の目的関数値
+    pub objective: f64,
+    /// ファイバ数の上限を満たすかどうか
+    pub feasible: bool,
+    pub accepted: bool,
+    /// その時点までの最良の目的関数値
+    pub best_objective: f64,
+}
+
+/// 設置するバイパス
+#[derive(Debug, Clone)]
+struct Bypass {
+    edges: Vec<Edge>,
+    /// 設置に用いるXCの組み合わせ
+    xc_types: Vec<XCType>,
+}
+
+/// 目的関数の基準とする従来NWの値
+struct Baseline {
+    w2w_fiber_count: usize,
+    max_wxc_size: usize,
+    wxc_pass_count: f64,
+    /// WSSの多段接続が必要なWXCの数
+    cascaded_xc_count: usize,
+}
+
+/// 解の評価結果
+#[derive(Clone)]
+struct Evaluation {
+    network: Network,
+    demand_list: Vec<Demand>,
+    objective: f64,
+    feasible: bool,
+}
+
+/// バイパスの集合を設置したネットワークにパスを割り当て，目的関数値を求める
+fn evaluate(
+    config: &Config,
+    topology: &Topology,
+    xc_types: &[XCType],
+    demand_list: &[Demand],
+    bypasses: &[Bypass],
+    baseline: &Baseline,
+) -> Evaluation {
+    let mut network = Network::new(config, topology, xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+    let mut demand_list = demand_list.to_vec();
+
+    for bypass in bypasses {
+        expander::expand_fibers_with_xc_types_install_edges(config, &mut network, &bypass.edges, &bypass.xc_types, &mut vec![]);
+    }
+    assign_all_paths(config, &mut network, topology, &mut demand_list);
+
+    // 使われなかったバイパスを撤去する
+    let mut taboo_list: Vec<SD> = vec![];
+    if xc_types.contains(&XCType::Wbxc) {
+        network.delete_empty_fibers_wb(config, &mut taboo_list);
+    } else {
+        network.delete_empty_fibers_core(config, &mut taboo_list);
+    }
+
+    let fiber_count_ratio = analysis::calc_fiber_count_ratio(&network, baseline.w2w_fiber_count);
+    let max_wxc_size = analysis::calc_max_xc_size(&network, &XCType::Wxc);
+    let wxc_pass_count = output::calc_wxc_pass_count_average(&network, &demand_list);
+    // 従来NWでWXCを通過しない (またはパス需要がない) 場合は比較できないため，比を1とする
+    let wxc_pass_ratio = if baseline.wxc_pass_count > 0.0 { wxc_pass_count / baseline.wxc_pass_count } else { 1.0 };
+
+    let objective = config.search.weight_fiber * fiber_count_ratio
+        + config.search.weight_max_wxc_size * max_wxc_size as f64 / baseline.max_wxc_size.max(1) as f64
+        + config.search.weight_wxc_pass * wxc_pass_ratio;
+    let feasible = fiber_count_ratio.is_finite()
+        && fiber_count_ratio <= 1.0 + config.network.fiber_increase_rate_limit
+        && network.count_wss_cascaded_xcs(config.network.wss_m) <= baseline.cascaded_xc_count;
+
+    Evaluation { network, demand_list, objective, feasible }
+}
+
+/// 現在の解で出現頻度の高い区間から，追加するバイパスを無作為に選ぶ
+fn pick_bypass(
+    config: &Config,
+    topology: &Topology,
+    xc_types: &[XCType],
+    current: &Evaluation,
+    rng: &mut ChaCha8Rng,
+) -> Option<Bypass> {
+    let bypass_len = rng.gen_range(MIN_BYPASS_LEN..=MAX_BYPASS_LEN);
+    let mut sd_counts = expander::find_emerge_sub_routes_sd_count_with_xc_types_with_len(
+        &current.network,
+        &current.demand_list,
+        &[],
+        xc_types,
+        bypass_len,
+    );
+    sd_counts.truncate(config.search.candidate_count);
+    if sd_counts.is_empty() {
+        return None;
+    }
+
+    let (sd, path_count) = sd_counts[rng.gen_range(0..sd_counts.len())];
+    route_bypass(config, topology, xc_types, &sd, path_count, rng)
+}
+
+/// 区間`sd`に設置するバイパスの経路とXCの組み合わせを選ぶ
+fn route_bypass(
+    config: &Config,
+    topology: &Topology,
+    xc_types: &[XCType],
+    sd: &SD,
+    path_count: usize,
+    rng: &mut ChaCha8Rng,
+) -> Option<Bypass> {
+    let route_cand = if config.network.fiber_unification {
+        get_fixed_shortest_path(topology, sd, None)
+    } else {
+        get_random_shortest_path(topology, sd, rng.gen_range(0..u64::MAX), None)
+    };
+    if route_cand.edge_route.len() <= 1 {
+        return None;
+    }
+
+    Some(Bypass {
+        edges: route_cand.edge_route,
+        xc_types: expander::select_bypass_xc_types(config, xc_types, path_count),
+    })
+}
+
+/// 貪欲法 (`designer::main`と同じ手順) で初期解を作る
+///
+/// バイパス長ごとに出現頻度の高い区間すべてにバイパスを追加し，
+/// 上限を満たさなければ出現頻度の最も低い区間を外して評価し直す．
+fn design_greedy(
+    config: &Config,
+    topology: &Topology,
+    xc_types: &[XCType],
+    demand_list: &[Demand],
+    baseline: &Baseline,
+    rng: &mut ChaCha8Rng,
+) -> (Vec<Bypass>, Evaluation) {
+    let mut bypasses: Vec<Bypass> = vec![];
+    let mut current = evaluate(config, topology, xc_types, demand_list, &bypasses, baseline);
+
+    for bypass_len in MIN_BYPASS_LEN..=MAX_BYPASS_LEN {
+        let sd_counts = expander::find_emerge_sub_routes_sd_count_with_xc_types_with_len(
+            &current.network,
+            &current.demand_list,
+            &[],
+            xc_types,
+            bypass_len,
+        );
+        let mut added_bypasses: Vec<Bypass> = sd_counts
+            .iter()
+            .filter_map(|(sd, path_count)| route_bypass(config, topology, xc_types, sd, *path_count, rng))
+            .collect();
+
+        while !added_bypasses.is_empty() {
+            let candidate_bypasses = [bypasses.as_slice(), added_bypasses.as_slice()].concat();
+            let evaluation = evaluate(config, topology, xc_types, demand_list, &candidate_bypasses, baseline);
+            if evaluation.feasible {
+                bypasses = candidate_bypasses;
+                current = evaluation;
+                break;
+            }
+            added_bypasses.pop();
+        }
+    }
+
+    (bypasses, current)
+}
+
+/// 焼きなまし法による設計
+///
+/// 設置するバイパスの集合を解とし，貪欲法による解から追加・撤去・移動の近傍操作を繰り返す．
+/// 近傍解はバイパスを設置した空のネットワークに全てのパスを割り当てて評価し，
+/// ファイバ数の上限を満たし，WSSの多段接続が必要なWXCが従来NWより増えない解のみ受理する．
+/// 目的関数はファイバ数の比，WXCの最大ポート数，平均WXC通過回数の従来NWに対する比の重み付き和．
+pub fn main(config: &Config, xc_types: &[XCType]) -> (Network, Topology, String) {
+    let output_dir: &str = &output::init_output_dir_wo_suffix(config);
+    output::save_config(config, output_dir);
+    output::save_connection(output_dir);
+
+    let topology = Topology::new(config);
+
+    // 従来NW
+    let mut network = Network::new(config, &topology, xc_types);
+    network.update_layer_topologies(topology.route_candidates.clone(), &[]);
+    let mut demand_list = demand::get_demand_list(config, &topology);
+    assign_all_paths(config, &mut network, &topology, &mut demand_list);
+
+    let conv_nw_w2w_fiber_count = *network
+        .get_fiber_breakdown()
+        .get(&[XCType::Wxc, XCType::Wxc])
+        .unwrap_or(&0);
+    debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+    output::save_conv_output(config, output_dir, &network, &demand_list);
+
+    let baseline = Baseline {
+        w2w_fiber_count: conv_nw_w2w_fiber_count,
+        max_wxc_size: analysis::calc_max_xc_size(&network, &XCType::Wxc),
+        wxc_pass_count: output::calc_wxc_pass_count_average(&network, &demand_list),
+        cascaded_xc_count: network.count_wss_cascaded_xcs(config.network.wss_m),
+    };
+    delete_all_paths(&mut network, &mut demand_list);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(config.simulation.random_seed);
+    let (mut current_bypasses, mut current) =
+        design_greedy(config, &topology, xc_types, &demand_list, &baseline, &mut rng);
+    let mut best = current.clone();
+    let mut temperature = config.search.initial_temperature;
+    let mut history: Vec<SearchStep> = vec![SearchStep {
+        iteration: 0,
+        search_move: SearchMove::Greedy,
+        bypass_count: current_bypasses.len(),
+        objective: current.objective,
+        feasible: current.feasible,
+        accepted: true,
+        best_objective: best.objective,
+    }];
+
+    let start = Instant::now();
+    for iteration in 1..=config.search.max_iterations {
+        if start.elapsed().as_secs_f64() > config.search.time_limit {
+            break;
+        }
+
+        let search_move = if current_bypasses.is_empty() {
+            SearchMove::Add
+        } else {
+            [SearchMove::Add, SearchMove::Remove, SearchMove::Move][rng.gen_range(0..3)]
+        };
+
+        let mut candidate_bypasses = current_bypasses.clone();
+        if search_move != SearchMove::Add {
+            candidate_bypasses.remove(rng.gen_range(0..candidate_bypasses.len()));
+        }
+        if search_move != SearchMove::Remove {
+            let Some(bypass) = pick_bypass(config, &topology, xc_types, &current, &mut rng) else {
+                temperature *= config.search.cooling_rate;
+                continue;
+            };
+            candidate_bypasses.push(bypass);
+        }
+
+        let evaluation = evaluate(config, &topology, xc_types, &demand_list, &candidate_bypasses, &baseline);
+        let delta = evaluation.objective - current.objective;
+        let accepted = evaluation.feasible && (delta <= 0.0 || rng.gen::<f64>() < (-delta / temperature).exp());
+
+        let mut step = SearchStep {
+            iteration,
+            search_move,
+            bypass_count: candidate_bypasses.len(),
+            objective: evaluation.objective,
+            feasible: evaluation.feasible,
+            accepted,
+            best_objective: best.objective,
+        };
+
+        if accepted {
+            current_bypasses = candidate_bypasses;
+            current = evaluation;
+            if current.objective < best.objective {
+                best = current.clone();
+            }
+        }
+        step.best_objective = best.objective;
+        history.push(step);
+
+        temperature *= config.search.cooling_rate;
+    }
+
+    let Evaluation { mut network, mut demand_list, .. } = best;
+    debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+    output::save_output(config, output_dir, &network, &demand_list);
+    output::save_search_history(output_dir, &history);
+
+    delete_all_paths(&mut network, &mut demand_list);
+
+    (network, topology, output_dir.to_string())
+}
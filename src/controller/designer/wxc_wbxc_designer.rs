@@ -29,40 +29,58 @@ pub fn main(config: &Config) -> (Network, Topology, String) {
     // 従来NWの情報を記録
     let conv_nw_w2w_fiber_count = *network.get_fiber_breakdown().get(&[XCType::Wxc, XCType::Wxc]).unwrap_or(&0);
     debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
-    output::save_conv_output(output_dir, &network, &demand_list);
+    output::save_conv_output(config, output_dir, &network, &demand_list);
 
     // 設立後埋まらなかった区間をタブーに追加
     let mut taboo_list: Vec<SD> = vec![];
 
-    // // レイヤ化NWの設計
+    // レイヤ化NWの設計
+    // FXCの設計と同様に，ファイバ増加率が上限以下となるバイパスのみ採用する
     loop {
         // まとめられるパスを探す
-        let target_edge_route = {
-            let sd = expander::find_frequently_emerge_sub_routes_sd_with_xc_types(&network, &demand_list, &taboo_list, &xc_types).unwrap();
-            let route_candidate = get_random_shortest_path(&topology, &sd, network.rng.gen_range(0..u64::MAX), None);
-            if route_candidate.edge_route.len() == 1 {
-                taboo_list.push(sd);
-                debugger::log_taboo_list_addition(config, &sd);
-                continue;
-            }
-            route_candidate.edge_route
+        let Some(sd) = expander::find_frequently_emerge_sub_routes_sd_with_xc_types(&network, &demand_list, &taboo_list, &xc_types) else {
+            debugger::log_alert("There is no candidate for bypass; because all of candidates are regarded as taboo.");
+            break;
         };
+        let route_candidate = get_random_shortest_path(&topology, &sd, network.rng.gen_range(0..u64::MAX), None);
+        if route_candidate.edge_route.len() == 1 {
+            taboo_list.push(sd);
+            debugger::log_taboo_list_addition(config, &sd);
+            continue;
+        }
+        let target_edge_route = route_candidate.edge_route;
 
-        // 全てのパスを削除 + バイパスファイバ配置 + 全てのパスを再配置
-        delete_all_paths(&mut network, &mut demand_list);
-        //expander::expand_fibers_with_xc_types(config, &mut network, &target_edge_route, &[XCType::Wxc, XCType::Wbxc]);
-        assign_all_paths(config, &mut network, &topology, &mut demand_list);
+        // 不採用の場合に取り消せるよう，トランザクション中にバイパスを設置する
+        let wss_cascaded_xc_count = network.count_wss_cascaded_xcs(config.network.wss_m);
+        network.begin();
+        let mut working_demand_list = demand_list.clone();
+        let mut working_taboo_list = taboo_list.clone();
 
-        // 使用していないファイバを削除
-        network.delete_empty_fibers_wb(config, &mut taboo_list);
-        network.delete_empty_fibers(config, &mut taboo_list);
+        // 全てのパスを削除 + WBバイパス設置 + 全てのパスを再配置
+        delete_all_paths(&mut network, &mut working_demand_list);
+        expander::expand_wbxc_fibers(config, &mut network, &target_edge_route);
+        assign_all_paths(config, &mut network, &topology, &mut working_demand_list);
 
-        debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &demand_list);
+        // 使用していないWBバイパスとファイバを削除
+        network.delete_empty_fibers_wb(config, &mut working_taboo_list);
 
-        // 終了判定
+        debugger::log_analysis(config, &network, conv_nw_w2w_fiber_count, &working_demand_list);
+
+        // 採用判定
+        // WSSの多段接続が必要なWXCを増やすバイパスは採用しない
         let count_ratio = debugger::analysis::calc_fiber_count_ratio(&network, conv_nw_w2w_fiber_count);
-        if count_ratio.is_finite() && count_ratio > 1.0 + config.network.fiber_increase_rate_limit {
-            break;
+        if count_ratio.is_finite()
+            && count_ratio <= 1.0 + config.network.fiber_increase_rate_limit
+            && network.count_wss_cascaded_xcs(config.network.wss_m) <= wss_cascaded_xc_count
+        {
+            network.commit();
+            demand_list = working_demand_list;
+            taboo_list = working_taboo_list;
+        } else {
+            network.rollback();
+            // 上限を超える区間は再度候補としない
+            taboo_list.push(sd);
+            debugger::log_taboo_list_addition(config, &sd);
         }
     }
     // loop {
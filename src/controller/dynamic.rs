@@ -1,3 +1,4 @@
+use crate::debugger::analysis::{ average_fragmentation_metrics, calc_fragmentation_metrics, FragmentationMetrics };
 use crate::demand::Demand;
 use crate::np_core::parameters::{ PB_CHARS, PB_TEMPLATES };
 use crate::{
@@ -6,14 +7,35 @@ use crate::{
     demand::dynamic::get_dynamic_demand_list,
     network::Network,
     topology::Topology,
-    THREADS,
+    utils,
 };
 use indicatif::MultiProgress;
 use indicatif::{ ProgressBar, ProgressStyle };
 use rayon::prelude::*;
-use rayon::ThreadPoolBuilder;
 
 use super::ctrl_utils::assign;
+use defrag::defragment;
+pub use defrag::{ DefragStats, DefragTrigger };
+
+mod defrag;
+
+/// 断片化指標を記録する間隔 (到着数)
+const FRAGMENTATION_SAMPLE_INTERVAL: usize = 50;
+
+/// デフラグメンテーションの有無による呼損率の比較
+#[derive(Debug, Clone)]
+pub struct DefragComparison {
+    pub traffic_intensity: f64,
+    /// デフラグメンテーションなしの呼損率
+    pub blocking_rate_without: f64,
+    /// デフラグメンテーションありの呼損率
+    pub blocking_rate_with: f64,
+    pub stats: DefragStats,
+    /// デフラグメンテーションなしの断片化指標 (時間平均)
+    pub fragmentation_without: FragmentationMetrics,
+    /// デフラグメンテーションありの断片化指標 (時間平均)
+    pub fragmentation_with: FragmentationMetrics,
+}
 
 pub fn get_blocking_curve(
     config: &Config,
@@ -21,16 +43,11 @@ pub fn get_blocking_curve(
     topology: &Topology,
     traffic_intensity: &[f64]
 ) -> Vec<(f64, f64)> {
-    let pool = ThreadPoolBuilder::new()
-        .num_threads(THREADS)
-        .build()
-        .expect("Failed to create thread pool");
-
     let m = MultiProgress::new();
 
     let tis_pbs: Vec<(f64, ProgressBar)> = traffic_intensity.iter().map(|ti| (*ti, m.add(ProgressBar::new(config.traffic.path_num as u64)))).collect();
 
-    pool.install(|| {
+    utils::thread_pool().install(|| {
         tis_pbs
             .into_par_iter()
             .map(|(ti, pb)| {
@@ -49,6 +66,49 @@ pub fn get_blocking_curve(
     })
 }
 
+/// 設定されたデフラグメンテーションの有無で呼損率を比較する
+pub fn get_defrag_comparison(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    traffic_intensity: &[f64]
+) -> Vec<DefragComparison> {
+    let trigger = DefragTrigger::from_config(config);
+
+    utils::thread_pool().install(|| {
+        traffic_intensity
+            .par_iter()
+            .map(|&ti| {
+                let (blocking_rate_without, _, fragmentation_without) = dynamic_analysis_with_trigger(
+                    config,
+                    &mut network.clone(),
+                    topology,
+                    ti,
+                    Some(ProgressBar::hidden()),
+                    DefragTrigger::Disabled
+                );
+                let (blocking_rate_with, stats, fragmentation_with) = dynamic_analysis_with_trigger(
+                    config,
+                    &mut network.clone(),
+                    topology,
+                    ti,
+                    Some(ProgressBar::hidden()),
+                    trigger
+                );
+
+                DefragComparison {
+                    traffic_intensity: ti,
+                    blocking_rate_without,
+                    blocking_rate_with,
+                    stats,
+                    fragmentation_without,
+                    fragmentation_with,
+                }
+            })
+            .collect()
+    })
+}
+
 pub fn dynamic_analysis(
     config: &Config,
     network: &mut Network,
@@ -56,12 +116,34 @@ pub fn dynamic_analysis(
     traffic_intensity: f64,
     progressbar: Option<ProgressBar>
 ) -> f64 {
+    let (blocking_rate, _, _) = dynamic_analysis_with_trigger(
+        config,
+        network,
+        topology,
+        traffic_intensity,
+        progressbar,
+        DefragTrigger::from_config(config)
+    );
+    blocking_rate
+}
+
+/// 動的解析を行い，呼損率，デフラグメンテーションの統計，断片化指標の時間平均を返す
+fn dynamic_analysis_with_trigger(
+    config: &Config,
+    network: &mut Network,
+    topology: &Topology,
+    traffic_intensity: f64,
+    progressbar: Option<ProgressBar>,
+    defrag_trigger: DefragTrigger
+) -> (f64, DefragStats, FragmentationMetrics) {
     // パス需要
     let mut demand_list = get_dynamic_demand_list(config, topology, traffic_intensity);
 
     let mut assigned_demand_indices = vec![];
 
     let mut block_count = 0;
+    let mut defrag_stats = DefragStats::default();
+    let mut fragmentation_samples = vec![];
     let pb = match progressbar {
         Some(pb) => pb,
         None => ProgressBar::new(demand_list.len() as u64),
@@ -74,7 +156,20 @@ pub fn dynamic_analysis(
     for i in 0..demand_list.len() {
         let current_time = demand_list[i].start_time;
 
-        match assign(config, &mut demand_list[i], topology, network) {
+        let mut is_assigned = assign(config, &mut demand_list[i], topology, network);
+
+        // 呼損時にデフラグメンテーションを行い，再度割り当てる
+        if !is_assigned && defrag_trigger == DefragTrigger::Blocking {
+            defrag_stats.runs += 1;
+            defrag_stats.moves += defragment(config, network, topology, &mut demand_list, &assigned_demand_indices);
+
+            is_assigned = assign(config, &mut demand_list[i], topology, network);
+            if is_assigned {
+                defrag_stats.rescued += 1;
+            }
+        }
+
+        match is_assigned {
             true => {
                 assigned_demand_indices.push(i);
                 debugger::log_demand_assign(config, network, &demand_list[i]);
@@ -86,6 +181,19 @@ pub fn dynamic_analysis(
 
         dynamic_delete(network, &mut demand_list, current_time, &mut assigned_demand_indices);
 
+        if let DefragTrigger::Periodic(interval) = defrag_trigger {
+            if (i + 1) % interval == 0 {
+                defrag_stats.runs += 1;
+                defrag_stats.moves += defragment(config, network, topology, &mut demand_list, &assigned_demand_indices);
+            }
+        }
+
+        if (i + 1) % FRAGMENTATION_SAMPLE_INTERVAL == 0 {
+            let metrics = calc_fragmentation_metrics(network);
+            debugger::log_fragmentation(config, i + 1, &metrics);
+            fragmentation_samples.push(metrics);
+        }
+
         // debug
         debugger::log_state_matrix(config, network);
 
@@ -98,7 +206,7 @@ pub fn dynamic_analysis(
     //     format!("{:.2} {:.5}", traffic_intensity, blocking_rate)
     // );
 
-    blocking_rate
+    (blocking_rate, defrag_stats, average_fragmentation_metrics(&fragmentation_samples))
 }
 
 fn dynamic_delete(
@@ -0,0 +1,87 @@
+use crate::{ config::Config, demand::Demand, network::Network, topology::Topology };
+
+use super::super::{ ctrl_utils::apply_assignment_instruction, pathfinder };
+
+/// デフラグメンテーションの実行契機
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefragTrigger {
+    /// 実行しない
+    Disabled,
+    /// 指定した到着数ごとに実行する
+    Periodic(usize),
+    /// 呼損の発生時に実行し，同じDemandを再度割り当てる
+    Blocking,
+}
+
+impl DefragTrigger {
+    pub fn from_config(config: &Config) -> Self {
+        match config.defrag.trigger.to_uppercase().as_str() {
+            "NONE" => DefragTrigger::Disabled,
+            "PERIODIC" => {
+                assert!(config.defrag.interval > 0, "`interval` needs to be larger than 0");
+                DefragTrigger::Periodic(config.defrag.interval)
+            }
+            "BLOCKING" => DefragTrigger::Blocking,
+            _ => panic!("Invalid defrag `trigger`: {}", config.defrag.trigger),
+        }
+    }
+}
+
+/// デフラグメンテーションの統計
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefragStats {
+    /// 実行回数
+    pub runs: usize,
+    /// 再割当したDemand数
+    pub moves: usize,
+    /// 呼損となったが，デフラグメンテーション後の再割当で収容できたDemand数
+    pub rescued: usize,
+}
+
+/// 稼働中のDemandを再割当し，スペクトルを低インデックス側へ集約する
+///
+/// 使用スロットの大きいDemandから順に，現在のパスを残したまま (make-before-break) 新しいパスを探索する．
+/// 新しいパスのスロットが現在より小さく，使用ファイバ数が増えない場合のみ切り替える．
+/// 探索は既存のファイバのみを用いるため，FXC/WBXCのバイパスの接続はそのまま維持される．
+/// 予備パスを持つDemandは対象外．戻り値は再割当したDemand数．
+pub fn defragment(
+    config: &Config,
+    network: &mut Network,
+    topology: &Topology,
+    demand_list: &mut [Demand],
+    assigned_demand_indices: &[usize],
+) -> usize {
+    let mut target_demand_indices: Vec<usize> = assigned_demand_indices
+        .iter()
+        .copied()
+        .filter(|&index| demand_list[index].backup.is_none())
+        .collect();
+    target_demand_indices.sort_by_key(|&index| (std::cmp::Reverse(demand_list[index].slot_heads[0]), index));
+
+    let mut moves = 0;
+    for index in target_demand_indices {
+        if moves >= config.defrag.max_moves {
+            break;
+        }
+
+        let demand = &mut demand_list[index];
+
+        // 現在のパスが占有したまま探索するため，新しいパスは現在のパスと資源を共有しない
+        let Some(assignment_instruction) = pathfinder::search_unprotected(config, demand, topology, network) else {
+            continue;
+        };
+        if assignment_instruction.slot_head[0] >= demand.slot_heads[0]
+            || assignment_instruction.fiber_ids.len() > demand.fiber_ids.len()
+        {
+            continue;
+        }
+
+        // 新しいパスの確立後に現在のパスを撤去する
+        // Fiberは同じDemandを重複して保持できないため，管理上は撤去してから割り当てる
+        network.remove_path(demand);
+        apply_assignment_instruction(network, demand, assignment_instruction);
+        moves += 1;
+    }
+
+    moves
+}
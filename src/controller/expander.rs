@@ -1,19 +1,18 @@
 use std::cmp::Reverse;
 
-use expand_fxc::{expand_fxc_fibers, expand_fxc_fibers_install_edges};
+use expand_fxc::{expand_added_wxc_fxc_fibers_install_edges, expand_fxc_fibers_install_edges};
 use fxhash::FxHashMap;
 
 use crate::{
     config::Config,
     demand::Demand,
     network::{CoreIndex, Fiber, FiberID, Network, PortID, XCType},
-    np_core::parameters::{MAX_BYPASS_LEN, MIN_BYPASS_LEN},
+    np_core::parameters::{MAX_BYPASS_LEN, MIN_BYPASS_LEN, SLOT},
     utils::enumerate_subsequences,
     Edge, SD,
 };
 
 mod expand_sxc;
-pub use expand_sxc::expand_sxc_fibers;
 pub use expand_sxc::get_min_expand_route_cand;
 
 mod expand_fxc;
@@ -22,45 +21,52 @@ mod expand_wxc;
 pub use expand_wxc::expand_wxc_fibers;
 
 mod expand_wbxc;
-pub use expand_wbxc::expand_wbxc_fibers;
+pub use expand_wbxc::{expand_wbxc_fibers, expand_wbxc_fibers_install_edges};
 
 pub fn remove_fibers_by_edges(
     config: &Config,
     network: &mut Network,
     target: &[Edge],
 ) -> Vec<FiberID> {
-    let mut removed_fiber_ids = Vec::new();
-
-    for edge in target {
-        let fiber_ids_on_edge = network.get_fiber_id_on_edge(edge);
-        if let Some(first_w2w_fiber) = fiber_ids_on_edge
-            .iter()
-            .find(|x| network.get_fiber_sd_xc_type_by_id(x) == [XCType::Wxc, XCType::Wxc])
-        {
-            network.delete_fiber(config, first_w2w_fiber);
-            removed_fiber_ids.push(*first_w2w_fiber);
-        }
+    let removed_fiber_ids = find_fibers_to_remove(network, target);
+    for fiber_id in &removed_fiber_ids {
+        network.delete_fiber(config, fiber_id);
     }
 
     removed_fiber_ids
 }
+
+/// `remove_fibers_by_edges`で削除されるファイバ (各エッジの最初のW-Wファイバ)
+pub fn find_fibers_to_remove(network: &Network, target: &[Edge]) -> Vec<FiberID> {
+    target
+        .iter()
+        .filter_map(|edge| {
+            network
+                .get_fiber_id_on_edge(edge)
+                .into_iter()
+                .find(|x| network.get_fiber_sd_xc_type_by_id(x) == [XCType::Wxc, XCType::Wxc])
+        })
+        .collect()
+}
 pub fn generate_new_fiber(
     network: &mut Network,
     edge: &Edge,
     src_type: XCType,
     dst_type: XCType,
 ) -> Fiber {
+    let src_port_id = network.generate_port_id();
     let src_xc = network.get_xc_mut_on_node(edge.src.into(), &src_type);
     let src_xc_id = src_xc.id;
-    let src_device = src_xc.generate_new_device(false);
+    let src_device = src_xc.generate_new_device(src_port_id, false);
     network.regist_port_id(&src_device, &src_xc_id);
 
+    let dst_port_id = network.generate_port_id();
     let dst_xc = network.get_xc_mut_on_node(edge.dst.into(), &dst_type);
     let dst_xc_id = dst_xc.id;
-    let dst_device = dst_xc.generate_new_device(true);
+    let dst_device = dst_xc.generate_new_device(dst_port_id, true);
     network.regist_port_id(&dst_device, &dst_xc_id);
 
-    Fiber::new_scf(edge, src_device, dst_device, [src_type, dst_type])
+    Fiber::new_scf(network.generate_fiber_id(), edge, src_device, dst_device, [src_type, dst_type])
 }
 
 fn generate_new_mc_fiber(
@@ -69,27 +75,33 @@ fn generate_new_mc_fiber(
     src_type: XCType,
     dst_type: XCType,
 ) -> Fiber {
-    let src_xc = network.get_xc_mut_on_node(edge.src.into(), &src_type);
-    let src_xc_id = src_xc.id;
     let src_port_ids: Vec<PortID> = CoreIndex::iter()
         .iter()
-        .map(|_| src_xc.generate_new_device(false))
+        .map(|_| network.generate_port_id())
         .collect();
+    let src_xc = network.get_xc_mut_on_node(edge.src.into(), &src_type);
+    let src_xc_id = src_xc.id;
+    for src_device in src_port_ids.iter() {
+        src_xc.generate_new_device(*src_device, false);
+    }
     for src_device in src_port_ids.iter() {
         network.regist_port_id(src_device, &src_xc_id);
     }
 
-    let dst_xc = network.get_xc_mut_on_node(edge.dst.into(), &dst_type);
-    let dst_xc_id = dst_xc.id;
     let dst_port_ids: Vec<PortID> = CoreIndex::iter()
         .iter()
-        .map(|_| dst_xc.generate_new_device(true))
+        .map(|_| network.generate_port_id())
         .collect();
+    let dst_xc = network.get_xc_mut_on_node(edge.dst.into(), &dst_type);
+    let dst_xc_id = dst_xc.id;
+    for dst_device in dst_port_ids.iter() {
+        dst_xc.generate_new_device(*dst_device, true);
+    }
     for dst_device in dst_port_ids.iter() {
         network.regist_port_id(dst_device, &dst_xc_id);
     }
 
-    Fiber::new_mcf(edge, src_port_ids, dst_port_ids, [src_type, dst_type])
+    Fiber::new_mcf(network.generate_fiber_id(), edge, src_port_ids, dst_port_ids, [src_type, dst_type])
 }
 
 /// 二層まで対応，三層以上の場合，トップレイヤを始端・終端とするバイパスのみ認める
@@ -111,10 +123,11 @@ pub fn find_frequently_emerge_sub_routes_sd_with_xc_types(
 
     // 全ての`Demand`に対して，`fiber_route`から`edge_route`を取得，
     // 連続部分列を列挙し，カウンタに追加する
-    for demand in demand_list {
-        if !demand.fiber_ids.is_empty() {
+    // 予備パスも現用パスと同様に数える
+    for fiber_route in demand_list.iter().flat_map(|demand| demand.get_fiber_routes()) {
+        if !fiber_route.is_empty() {
             let sub_routes =
-                enumerate_subsequences(&demand.fiber_ids, MIN_BYPASS_LEN, Some(MAX_BYPASS_LEN));
+                enumerate_subsequences(fiber_route, MIN_BYPASS_LEN, Some(MAX_BYPASS_LEN));
 
             for sub_route in sub_routes {
                 // `sub_route`の始端/終端XCを確認する
@@ -188,19 +201,22 @@ pub fn find_frequently_emerge_sub_routes_sd_with_xc_types(
     // 全てのルートがタブーリストに入れられてしまった
     None
 }
-pub fn find_emerge_sub_routes_sd_with_xc_types_with_len(
+
+/// 指定長の部分経路の始終点SDと，その出現回数を出現頻度の降順で返す
+pub fn find_emerge_sub_routes_sd_count_with_xc_types_with_len(
     network: &Network,
     demand_list: &[Demand],
     taboo_list: &[SD],
     xc_types: &[XCType],
     bypass_len: usize,
-) -> Vec<SD> {
+) -> Vec<(SD, usize)> {
     let mut counter: FxHashMap<SD, usize> = FxHashMap::default();
 
-    for demand in demand_list {
-        if !demand.fiber_ids.is_empty() {
+    // 予備パスも現用パスと同様に数える
+    for fiber_route in demand_list.iter().flat_map(|demand| demand.get_fiber_routes()) {
+        if !fiber_route.is_empty() {
             let sub_routes =
-                enumerate_subsequences(&demand.fiber_ids, bypass_len, Some(bypass_len));
+                enumerate_subsequences(fiber_route, bypass_len, Some(bypass_len));
 
             for sub_route in sub_routes {
                 let first_fiber = network.get_fiber_by_id(sub_route.first().unwrap());
@@ -239,13 +255,28 @@ pub fn find_emerge_sub_routes_sd_with_xc_types_with_len(
     counter_vec.sort_by_key(|(_sd, count)| Reverse(*count));
 
     // タブーリストに含まれていないSDだけを返す
-    let result: Vec<SD> = counter_vec
+    counter_vec
         .into_iter()
         .filter(|(sd, _)| !taboo_list.contains(sd))
-        .map(|(sd, _)| sd)
-        .collect();
+        .collect()
+}
 
-    result
+/// バイパスの設置に用いるXCの組み合わせを選択する
+///
+/// WXC + WBXC + FXC の三層構成では，部分経路を通るパス数が
+/// `SLOT * fiber_bypass_ratio` 以上であればファイババイパス (FXC)，未満であればWBバイパス (WBXC) を選ぶ．
+/// それ以外の構成ではノード構成をそのまま返す．
+pub fn select_bypass_xc_types(config: &Config, xc_types: &[XCType], path_count: usize) -> Vec<XCType> {
+    match xc_types {
+        [XCType::Wxc, XCType::Wbxc, XCType::Fxc] => {
+            if path_count as f64 >= SLOT as f64 * config.network.fiber_bypass_ratio {
+                vec![XCType::Wxc, XCType::Fxc]
+            } else {
+                vec![XCType::Wxc, XCType::Wbxc]
+            }
+        }
+        _ => xc_types.to_vec(),
+    }
 }
 
 pub fn expand_fibers_with_xc_types_install_edges(
@@ -256,12 +287,12 @@ pub fn expand_fibers_with_xc_types_install_edges(
     all_installed_edge: &mut Vec<Vec<Edge>>,
 ) -> (Vec<Fiber>, Vec<(Edge, XCType, XCType)>) {
     match xc_types {
-        //[XCType::Wxc, XCType::Wbxc] => expand_wbxc_fibers(config, network, target_edges),
+        [XCType::Wxc, XCType::Wbxc] => expand_wbxc_fibers_install_edges(config, network, target_edges),
         [XCType::Wxc, XCType::Fxc] => {
             expand_fxc_fibers_install_edges(config, network, target_edges, all_installed_edge)
         }
         [XCType::Wxc, XCType::Added_Wxc, XCType::Fxc] => {
-            expand_fxc_fibers_install_edges(config, network, target_edges, all_installed_edge)
+            expand_added_wxc_fxc_fibers_install_edges(config, network, target_edges, all_installed_edge)
         }
         //[XCType::Wxc, XCType::Sxc] => expand_sxc_fibers(config, network, target_edges),
         [XCType::Wbxc, XCType::Fxc] | [XCType::Wbxc, XCType::Sxc] | [XCType::Fxc, XCType::Sxc] => {
@@ -277,34 +308,11 @@ pub fn expand_fibers_with_xc_types_install_edges(
         | [XCType::Wbxc, XCType::Wbxc]
         | [XCType::Fxc, XCType::Fxc]
         | [XCType::Sxc, XCType::Sxc] => panic!("XCTypes Error"),
-        [XCType::Wbxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Fxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Sxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Wxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Wbxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Fxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Sxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Added_Wxc] => todo!(),
+        // Added_WxcはFXCの手前に置く補助WXCであり，単独でバイパスの層とはならない
+        // `controller::parse_node_configuration`は[Wxc, Added_Wxc, Fxc]以外でAdded_Wxcを返さない
+        [_, XCType::Added_Wxc] | [XCType::Added_Wxc, _] => {
+            unreachable!("Added_Wxc is only used in the [Wxc, Added_Wxc, Fxc] configuration: {:?}", xc_types)
+        }
         _ => panic!("Invalid combination of XC types"),
     }
 }
-
-pub fn expand_fibers_with_xc_types(config: &Config, network: &mut Network, target_edges: &[Edge], xc_types: &[XCType; 2]) {
-    match *xc_types{
-        [XCType::Wxc, XCType::Wbxc] => expand_wbxc_fibers(config, network, target_edges),
-        [XCType::Wxc, XCType::Fxc]  => expand_fxc_fibers(config, network, target_edges),
-        [XCType::Wxc, XCType::Sxc]  => expand_sxc_fibers(config, network, target_edges),
-        [XCType::Wbxc, XCType::Fxc] | [XCType::Wbxc, XCType::Sxc] | [XCType::Fxc, XCType::Sxc] => unimplemented!(),
-        [XCType::Wbxc, XCType::Wxc] | [XCType::Fxc, XCType::Wxc] | [XCType::Fxc, XCType::Wbxc] | [XCType::Sxc, XCType::Wxc] | [XCType::Sxc, XCType::Wbxc]| [XCType::Sxc, XCType::Fxc] => panic!("Ordering Error"),
-        [XCType::Wxc, XCType::Wxc] | [XCType::Wbxc, XCType::Wbxc] | [XCType::Fxc, XCType::Fxc] | [XCType::Sxc, XCType::Sxc] => panic!("XCTypes Error"),
-        [XCType::Wxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Wbxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Fxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Sxc, XCType::Added_Wxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Wxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Wbxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Fxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Sxc] => todo!(),
-        [XCType::Added_Wxc, XCType::Added_Wxc] => todo!(),
-    }
-}
@@ -14,6 +14,27 @@ pub fn expand_fxc_fibers_install_edges(
     network: &mut Network,
     target_edges: &[Edge],
     all_installed_edges: &mut Vec<Vec<Edge>>, // ← mutable参照に変更
+) -> (Vec<Fiber>, Vec<(Edge, XCType, XCType)>) {
+    install_fxc_bypass(config, network, target_edges, all_installed_edges, XCType::Wxc)
+}
+
+/// 入口に補助WXCを置いたFXCバイパスを新設する
+/// 終端は通常のWXCに接続する
+pub fn expand_added_wxc_fxc_fibers_install_edges(
+    config: &Config,
+    network: &mut Network,
+    target_edges: &[Edge],
+    all_installed_edges: &mut Vec<Vec<Edge>>,
+) -> (Vec<Fiber>, Vec<(Edge, XCType, XCType)>) {
+    install_fxc_bypass(config, network, target_edges, all_installed_edges, XCType::Added_Wxc)
+}
+
+fn install_fxc_bypass(
+    config: &Config,
+    network: &mut Network,
+    target_edges: &[Edge],
+    _all_installed_edges: &mut Vec<Vec<Edge>>,
+    entry_xc_type: XCType,
 ) -> (Vec<Fiber>, Vec<(Edge, XCType, XCType)>) {
     if target_edges.len() < 2 {
         panic!(
@@ -27,11 +48,11 @@ pub fn expand_fxc_fibers_install_edges(
     let mut fibers: Vec<Fiber> = vec![];
     let mut edge_type_tuples: Vec<(Edge, XCType, XCType)> = vec![];
 
-    // 最初のファイバ (WXC → FXC)
+    // 最初のファイバ (WXC/補助WXC → FXC)
     let first_edge = target_edges.first().unwrap();
-    let first_fiber = generate_new_fiber(network, first_edge, XCType::Wxc, XCType::Fxc);
+    let first_fiber = generate_new_fiber(network, first_edge, entry_xc_type, XCType::Fxc);
     let mut prev_dst_device_id = first_fiber.dst_port_ids.clone();
-    edge_type_tuples.push((*first_edge, XCType::Wxc, XCType::Fxc));
+    edge_type_tuples.push((*first_edge, entry_xc_type, XCType::Fxc));
     fibers.push(first_fiber);
 
     // 中間ファイバ群 (FXC → FXC)
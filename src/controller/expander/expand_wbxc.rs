@@ -1,10 +1,17 @@
 use fxhash::FxHashMap;
 
-use crate::{config::Config, debug_println, debugger, network::{FiberID, Network, PortID, XCType}, Edge, WBIndex};
+use crate::{config::Config, debug_println, debugger, network::{Fiber, FiberID, Network, PortID, XCType}, Edge, WBIndex};
 
 use super::generate_new_fiber;
 
 pub fn expand_wbxc_fibers(config: &Config, network: &mut Network, target_edges: &[Edge]) {
+    expand_wbxc_fibers_install_edges(config, network, target_edges);
+}
+
+/// WBバイパスを設置し，新設したファイバと (エッジ, 始端XC, 終端XC) の組を返す
+///
+/// 空きWBを持つ既存のファイバは再利用するため，戻り値には含まれない．
+pub fn expand_wbxc_fibers_install_edges(config: &Config, network: &mut Network, target_edges: &[Edge]) -> (Vec<Fiber>, Vec<(Edge, XCType, XCType)>) {
     
     if target_edges.len() < 2 {
         panic!("`target_edges` should be longer than two");
@@ -12,6 +19,8 @@ pub fn expand_wbxc_fibers(config: &Config, network: &mut Network, target_edges:
 
     let (wb_index, fiber_seq) = get_min_expand_wb_fiber_sequences(network, target_edges);
     let mut prev_dst_port_id = PortID::nil();
+    let mut fibers: Vec<Fiber> = vec![];
+    let mut edge_type_tuples: Vec<(Edge, XCType, XCType)> = vec![];
 
     for (idx, (fiber_id, target_edge)) in fiber_seq.iter().zip(target_edges.iter()).enumerate() {
         let target_fiber = if let Some(target_fiber_id) = fiber_id {
@@ -25,6 +34,9 @@ pub fn expand_wbxc_fibers(config: &Config, network: &mut Network, target_edges:
                 generate_new_fiber(network, target_edge, XCType::Wbxc, XCType::Wbxc)
             };
             debugger::log_fiber_expand(config, network, &fiber);
+            let [src_type, dst_type] = fiber.sd_xc_type;
+            edge_type_tuples.push((*target_edge, src_type, dst_type));
+            fibers.push(fiber.clone());
             network.regist_fiber(fiber)
         };
 
@@ -46,6 +58,8 @@ pub fn expand_wbxc_fibers(config: &Config, network: &mut Network, target_edges:
     }
 
     debugger::log_wb_bypass(config, target_edges, &wb_index);
+
+    (fibers, edge_type_tuples)
 }
 
 fn get_fiber_contains_unused_wb_specified(network: &Network, fiber_ids: &[FiberID], wb_index: &WBIndex, sd_xc_type: &[XCType; 2]) -> Option<FiberID> {
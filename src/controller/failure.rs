@@ -0,0 +1,224 @@
+use fxhash::FxHashSet;
+use rayon::iter::{ IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator };
+use serde_derive::{ Deserialize, Serialize };
+
+use crate::{ config::Config, demand::Demand, network::{ CoreIndex, FiberID, Network, XCID }, topology::Topology, utils, Edge };
+
+use super::{ ctrl_utils::restore, output };
+
+/// 故障シナリオごとの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureResult {
+    /// シナリオ名
+    pub scenario: String,
+    /// 現用パスが故障の影響を受けたDemand数
+    pub affected: usize,
+    /// 予備パスへ切り替えたDemand数
+    pub switched: usize,
+    /// 経路の再計算により復旧したDemand数
+    pub restored: usize,
+    /// 復旧できなかったDemand数
+    pub lost: usize,
+}
+
+/// 故障の種類ごとの結果 (故障の種類, 各シナリオの結果)
+pub type FailureResultsByKind = Vec<(String, Vec<FailureResult>)>;
+
+/// 設定に応じて故障シミュレーションを行い，結果を`output_dir`へ保存する
+pub fn run_failure_analysis(
+    config: &Config,
+    output_dir: &str,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+) -> FailureResultsByKind {
+    let mut all_results = vec![];
+
+    if config.failure.link_failure {
+        let results = simulate_single_link_failures(config, network, topology, demand_list);
+        all_results.push(("link".to_string(), results));
+    }
+    if config.failure.node_failure {
+        let results = simulate_single_node_failures(config, network, topology, demand_list);
+        all_results.push(("node".to_string(), results));
+    }
+    if config.failure.xc_failure {
+        let results = simulate_single_xc_failures(config, network, topology, demand_list);
+        all_results.push(("xc".to_string(), results));
+    }
+    if config.failure.srlg_failure {
+        let results = simulate_single_srlg_failures(config, network, topology, demand_list);
+        all_results.push(("srlg".to_string(), results));
+    }
+
+    for (name, results) in &all_results {
+        output::save_failure_results(output_dir, name, results);
+    }
+
+    all_results
+}
+
+/// 全ての単一リンク故障について故障シミュレーションを行う
+pub fn simulate_single_link_failures(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+) -> Vec<FailureResult> {
+    // 双方向のリンクは1本の物理リンクとして扱う
+    let links: Vec<Edge> = network
+        .edges
+        .iter()
+        .filter(|edge| edge.src < edge.dst)
+        .copied()
+        .collect();
+
+    utils::thread_pool().install(|| {
+        links
+            .into_par_iter()
+            .map(|edge| {
+                simulate_failure(config, network, topology, demand_list, &format!("{}", edge), |nw| {
+                    nw.fail_edges(&[edge])
+                })
+            })
+            .collect()
+    })
+}
+
+/// トポロジに定義された全てのSRLGについて，SRLGに属するリンクが同時に故障する場合の故障シミュレーションを行う
+pub fn simulate_single_srlg_failures(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+) -> Vec<FailureResult> {
+    utils::thread_pool().install(|| {
+        topology.srlgs
+            .par_iter()
+            .enumerate()
+            .map(|(srlg_id, srlg)| {
+                simulate_failure(config, network, topology, demand_list, &format!("SRLG{}", srlg_id), |nw| {
+                    nw.fail_edges(srlg)
+                })
+            })
+            .collect()
+    })
+}
+
+/// 全ての単一ノード故障について故障シミュレーションを行う
+/// 故障ノードを始点・終点とするDemandは復旧できない
+pub fn simulate_single_node_failures(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+) -> Vec<FailureResult> {
+    utils::thread_pool().install(|| {
+        (0..topology.link_matrix.len())
+            .into_par_iter()
+            .map(|node| {
+                simulate_failure(config, network, topology, demand_list, &format!("Node{}", node), |nw| {
+                    nw.fail_node(node)
+                })
+            })
+            .collect()
+    })
+}
+
+/// 全ての単一XC故障について故障シミュレーションを行う
+/// 例えばFXCのみが故障した場合，同じノードのWXCは動作を続ける
+pub fn simulate_single_xc_failures(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+) -> Vec<FailureResult> {
+    let mut xcs: Vec<(usize, String, XCID)> = network.xcs
+        .values()
+        .map(|xc| (xc.node, format!("{}", xc.xc_type), xc.id))
+        .collect();
+    xcs.sort();
+
+    utils::thread_pool().install(|| {
+        xcs
+            .into_par_iter()
+            .map(|(node, xc_type, xc_id)| {
+                simulate_failure(config, network, topology, demand_list, &format!("{}@{}", xc_type, node), |nw| {
+                    nw.fail_xc(xc_id)
+                })
+            })
+            .collect()
+    })
+}
+
+/// 故障を発生させ，影響を受けたDemandを復旧する
+///
+/// 予備パスが生存し，そのスロットを他のDemandが切替に使用していない場合は予備パスへ切り替え，
+/// それ以外は生存しているネットワーク上で経路を再計算する．
+/// 元の`network`, `demand_list`は変更しない．
+pub fn simulate_failure(
+    config: &Config,
+    network: &Network,
+    topology: &Topology,
+    demand_list: &[Demand],
+    scenario: &str,
+    fail: impl Fn(&mut Network),
+) -> FailureResult {
+    let mut network = network.clone();
+    let mut demand_list = demand_list.to_vec();
+
+    fail(&mut network);
+
+    let affected_demand_indices = network.get_affected_demand_indices(&demand_list);
+
+    // 影響を受けたパスを先に全て撤去し，解放された容量を復旧に利用する
+    // 共有予備スロットは先に切り替えたDemandが使用し，既に使用されている場合は経路を再計算する
+    let mut switched = 0;
+    let mut restore_demand_indices = vec![];
+    let mut claimed_slots: FxHashSet<(FiberID, CoreIndex, usize)> = FxHashSet::default();
+    for &index in &affected_demand_indices {
+        let demand = &mut demand_list[index];
+        let backup_slots: Option<Vec<(FiberID, CoreIndex, usize)>> = demand
+            .backup
+            .as_ref()
+            .filter(|backup| !network.is_fiber_route_failed(&backup.fiber_ids))
+            .map(|backup| {
+                backup.fiber_ids
+                    .iter()
+                    .zip(&backup.core_indices)
+                    .flat_map(|(fiber_id, core_index)| {
+                        (backup.slot_heads[0]..backup.slot_heads[0] + demand.slot_width)
+                            .map(|slot| (*fiber_id, *core_index, slot))
+                    })
+                    .collect()
+            });
+        let can_switch = backup_slots
+            .as_ref()
+            .is_some_and(|slots| slots.iter().all(|slot| !claimed_slots.contains(slot)));
+
+        if can_switch {
+            claimed_slots.extend(backup_slots.unwrap());
+            network.remove_working_path(demand);
+            switched += 1;
+        } else {
+            network.remove_path(demand);
+            demand.reset();
+            restore_demand_indices.push(index);
+        }
+    }
+
+    let mut restored = 0;
+    for &index in &restore_demand_indices {
+        if restore(config, &mut demand_list[index], topology, &mut network) {
+            restored += 1;
+        }
+    }
+
+    FailureResult {
+        scenario: scenario.to_string(),
+        affected: affected_demand_indices.len(),
+        switched,
+        restored,
+        lost: restore_demand_indices.len() - restored,
+    }
+}
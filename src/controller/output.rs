@@ -6,7 +6,9 @@ use std::{ env, fs };
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 
-use crate::network::{EdgesType,FiberType};
+use crate::debugger::analysis;
+use crate::controller::{designer::{iterative_designer::SeedScore, local_search::SearchStep}, dynamic::DefragComparison, failure::{FailureResult, FailureResultsByKind}};
+use crate::network::{power, protection::ProtectionMode, EdgesType,FiberType};
 use crate::np_core::parameters::{CURVE_GRAPH_SCRIPT, TRAVERSE_GRAPH_SCRIPT};
 use crate::utils::find_x_for_y;
 use crate::{Edge, Node};
@@ -99,6 +101,30 @@ pub fn save_best(output_dir: &str, best_str: &str) {
     let _f: File = get_file(&format!("{}/0_best_is_{}.txt", output_dir, best_str));
 }
  
+/// BESTモードで評価した全シードの指標を，選択基準の順に出力する
+pub fn save_best_ranking(output_dir: &str, scores: &[SeedScore]) {
+    let mut ranking: Vec<&SeedScore> = scores.iter().collect();
+    ranking.sort_by(|a, b| a.score.total_cmp(&b.score).then(a.index.cmp(&b.index)));
+
+    let mut f = get_mut_file(&format!("{output_dir}/0_best_ranking.txt"));
+    writeln!(f, "RANK INDEX SEED FIBER_RATIO MAX_WXC_SIZE WXC_PASS COST SCORE OUTDIR").unwrap();
+    for (rank, s) in ranking.iter().enumerate() {
+        writeln!(
+            f,
+            "{} {} {} {:.5} {} {:.5} {:.1} {:.5} {}",
+            rank + 1,
+            s.index,
+            s.seed,
+            s.fiber_ratio,
+            s.max_wxc_size,
+            s.wxc_pass,
+            s.cost,
+            s.score,
+            s.output_dir,
+        ).unwrap();
+    }
+}
+
 pub fn save_taboo_list(output_dir: &str, taboo_list: &[SD]) {
     let mut file: File = get_file(&format!("{}/taboo_list.txt", output_dir));
     for t in taboo_list {
@@ -106,13 +132,65 @@ pub fn save_taboo_list(output_dir: &str, taboo_list: &[SD]) {
     }
 }
  
+/// 故障シナリオごとの復旧結果
+pub fn save_failure_results(output_dir: &str, name: &str, results: &[FailureResult]) {
+    let mut f = get_mut_file(&format!("{output_dir}/failure_{name}.txt"));
+    writeln!(f, "SCENARIO AFFECTED SWITCHED RESTORED LOST").unwrap();
+
+    let (mut affected, mut switched, mut restored, mut lost) = (0, 0, 0, 0);
+    for result in results {
+        writeln!(f, "{} {} {} {} {}", result.scenario, result.affected, result.switched, result.restored, result.lost).unwrap();
+        affected += result.affected;
+        switched += result.switched;
+        restored += result.restored;
+        lost += result.lost;
+    }
+
+    writeln!(f, "TOTAL {} {} {} {}", affected, switched, restored, lost).unwrap();
+    // 影響を受けたDemandがなければ全て復旧したとみなす
+    let recovery_ratio = if affected == 0 { 1.0 } else { (switched + restored) as f64 / affected as f64 };
+    writeln!(f, "RECOVERY_RATIO: {:.5}", recovery_ratio).unwrap();
+}
+
+/// 設計ごと・故障の種類ごとに故障シミュレーションの結果を集計する
+/// WXCのみの設計 (conv) と提案設計 (prop) の耐故障性を比較するために用いる
+pub fn save_failure_summary(output_dir: &str, designs: &[(&str, &FailureResultsByKind)]) {
+    if designs.iter().all(|(_, results)| results.is_empty()) {
+        return;
+    }
+
+    let mut f = get_mut_file(&format!("{output_dir}/failure_summary.txt"));
+    writeln!(f, "DESIGN FAILURE SCENARIOS AFFECTED SWITCHED RESTORED LOST RECOVERY_RATIO WORST_SCENARIO WORST_LOST").unwrap();
+
+    for (design, all_results) in designs {
+        for (name, results) in all_results.iter() {
+            let affected: usize = results.iter().map(|x| x.affected).sum();
+            let switched: usize = results.iter().map(|x| x.switched).sum();
+            let restored: usize = results.iter().map(|x| x.restored).sum();
+            let lost: usize = results.iter().map(|x| x.lost).sum();
+            let recovery_ratio = if affected == 0 { 1.0 } else { (switched + restored) as f64 / affected as f64 };
+            let (worst_scenario, worst_lost) = results
+                .iter()
+                .max_by_key(|x| x.lost)
+                .map_or(("-", 0), |x| (x.scenario.as_str(), x.lost));
+
+            writeln!(
+                f,
+                "{} {} {} {} {} {} {} {:.5} {} {}",
+                design, name, results.len(), affected, switched, restored, lost, recovery_ratio, worst_scenario, worst_lost
+            ).unwrap();
+        }
+    }
+}
+
 pub fn save_conv_output(
+    config: &Config,
     output_dir: &str,
     network: &Network,
     demand_list: &[Demand]
 ) {
     create_dir_all(format!("{output_dir}/conv/")).unwrap();
-    save_analytics(&format!("{output_dir}/conv/"), network, demand_list);
+    save_analytics(config, &format!("{output_dir}/conv/"), network, demand_list);
  
     // let filename_prefix = format!("{}/conv", format!("{output_dir}"));
     // let fiber_label = network.get_fiber_output();
@@ -134,7 +212,7 @@ pub fn save_output(
     demand_list: &[Demand]
 ) {
     create_dir_all(format!("{output_dir}/prop/")).unwrap();
-    save_analytics(&format!("{output_dir}/prop/"), network, demand_list);
+    save_analytics(config, &format!("{output_dir}/prop/"), network, demand_list);
     save_wxc_port_pass_count_img(config);
     save_add_drop_count(output_dir, network,demand_list);
     save_specific_fiber_info(output_dir, network, demand_list);
@@ -376,6 +454,7 @@ pub fn save_transition_counts_around_node(output_dir: &str, network: &Network, d
                 }
             })
             .then_with(|| a_third.cmp(&b_third))
+            .then_with(|| a.0.cmp(b.0))
     });
     
 
@@ -638,7 +717,7 @@ pub fn save_add_drop_count(output_dir: &str, network: &Network, demand_list: &[D
     writeln!(f, "Add/drop数: {}", total_add_drop).unwrap();
 }
  
-fn calc_wxc_pass_count(network: &Network, demand_list: &[Demand]) -> Vec<usize> {
+pub fn calc_wxc_pass_count(network: &Network, demand_list: &[Demand]) -> Vec<usize> {
     let mut wxc_pass_count = vec![];
  
     for demand in demand_list {
@@ -646,15 +725,14 @@ fn calc_wxc_pass_count(network: &Network, demand_list: &[Demand]) -> Vec<usize>
         for fiber_id in &demand.fiber_ids {
             let [src_type, dst_type] = network.get_fiber_sd_xc_type_by_id(fiber_id);
             
+            // 補助WXCのポートもWXCのポートとして数える
             match src_type{
-                XCType::Wxc  => count += 1,
+                XCType::Wxc | XCType::Added_Wxc => count += 1,
                 XCType::Wbxc | XCType::Fxc | XCType::Sxc => (),
-                XCType::Added_Wxc => todo!(),
             }
             match dst_type{
-                XCType::Wxc  => count += 1,
+                XCType::Wxc | XCType::Added_Wxc => count += 1,
                 XCType::Wbxc | XCType::Fxc | XCType::Sxc => (),
-                XCType::Added_Wxc => todo!(),
             }
         }
  
@@ -682,7 +760,7 @@ pub fn calc_wxc_pass_count_average(network: &Network, demand_list: &[Demand]) ->
  
 }
  
-fn save_analytics(output_dir: &str, network: &Network, demand_list: &[Demand]) {
+fn save_analytics(config: &Config, output_dir: &str, network: &Network, demand_list: &[Demand]) {
     save_fiber_breakdown(output_dir, network);
     save_fiber_breakdown_on_each_link(output_dir, network);
     save_wxc_port_pass_count(output_dir, network, demand_list);
@@ -691,6 +769,151 @@ fn save_analytics(output_dir: &str, network: &Network, demand_list: &[Demand]) {
     // save_network_capacity(output_dir, x, y1, y2);
     save_path_info(output_dir, network, demand_list);
     save_xc_scale(output_dir, network);
+    save_protection_stats(config, output_dir, network, demand_list);
+    save_fragmentation(output_dir, network);
+    save_layer_stats(output_dir, network, demand_list);
+    save_wss_report(config, output_dir, network);
+    save_cost_breakdown(config, output_dir, network, demand_list);
+    save_power_report(config, output_dir, network, demand_list);
+}
+
+/// XCごと・レイヤごとの消費電力と，トランスポンダを含めた合計を出力する
+fn save_power_report(config: &Config, output_dir: &str, network: &Network, demand_list: &[Demand]) {
+    let xc_powers = network.get_xc_powers(config);
+
+    let mut f = get_mut_file(&format!("{output_dir}/power_report.txt"));
+    writeln!(f, "NODE TYPE PORTS SWITCHES POWER_W").unwrap();
+    for xc_power in &xc_powers {
+        writeln!(
+            f,
+            "{} {} {} {} {:.1}",
+            xc_power.node,
+            xc_power.xc_type,
+            xc_power.ports,
+            xc_power.switches,
+            xc_power.power,
+        ).unwrap();
+    }
+    writeln!(f).unwrap();
+    writeln!(f, "LAYER POWER_W").unwrap();
+    for xc_type in XCType::iter() {
+        let layer_power: f64 = xc_powers.iter().filter(|p| p.xc_type == xc_type).map(|p| p.power).sum();
+        if layer_power > 0.0 {
+            writeln!(f, "{} {:.1}", xc_type, layer_power).unwrap();
+        }
+    }
+    writeln!(f, "TRANSPONDER {:.1}", power::calc_transponder_power(config, demand_list)).unwrap();
+    writeln!(f, "TOTAL {:.1}", network.calc_total_power(config, demand_list)).unwrap();
+}
+
+/// 設備コストの内訳をノードごと・レイヤごとに出力する
+fn save_cost_breakdown(config: &Config, output_dir: &str, network: &Network, demand_list: &[Demand]) {
+    let breakdown = network.calc_cost_breakdown(config, demand_list);
+
+    let mut f = get_mut_file(&format!("{output_dir}/cost_breakdown.txt"));
+    writeln!(f, "NODE FIBER PORT TRANSCEIVER TOTAL").unwrap();
+    for (node, items) in breakdown.by_node() {
+        writeln!(f, "{} {:.1} {:.1} {:.1} {:.1}", node, items.fiber, items.port, items.transceiver, items.total()).unwrap();
+    }
+    writeln!(f).unwrap();
+    writeln!(f, "LAYER FIBER PORT TRANSCEIVER TOTAL").unwrap();
+    for (xc_type, items) in breakdown.by_layer() {
+        writeln!(f, "{} {:.1} {:.1} {:.1} {:.1}", xc_type, items.fiber, items.port, items.transceiver, items.total()).unwrap();
+    }
+    writeln!(f).unwrap();
+    let total = breakdown.total();
+    writeln!(f, "TOTAL {:.1} {:.1} {:.1} {:.1}", total.fiber, total.port, total.transceiver, total.total()).unwrap();
+}
+
+/// WXCごとの1xM WSSの構成を出力する
+/// 方路数がMを超えるWXCは多段接続が必要となり，段数に応じて挿入損失が増える
+fn save_wss_report(config: &Config, output_dir: &str, network: &Network) {
+    let wss_m = config.network.wss_m;
+    let usages = network.get_wss_usages(wss_m);
+
+    let mut f = get_mut_file(&format!("{output_dir}/wss_report.txt"));
+    writeln!(f, "WSS: 1x{}, INSERTION_LOSS: {:.1} dB", wss_m, config.network.wss_insertion_loss).unwrap();
+    writeln!(f, "NODE TYPE DEGREE STAGES WSS_COUNT EXTRA_LOSS_DB CASCADE").unwrap();
+    for usage in &usages {
+        writeln!(
+            f,
+            "{} {} {} {} {} {:.1} {}",
+            usage.node,
+            usage.xc_type,
+            usage.degree,
+            usage.stages,
+            usage.wss_count,
+            (usage.stages - 1) as f64 * config.network.wss_insertion_loss,
+            if usage.needs_cascade() { "YES" } else { "-" },
+        ).unwrap();
+    }
+    writeln!(f, "CASCADED_XCS: {}", usages.iter().filter(|usage| usage.needs_cascade()).count()).unwrap();
+    writeln!(f, "TOTAL_WSS_COUNT: {}", usages.iter().map(|usage| usage.wss_count).sum::<usize>()).unwrap();
+}
+
+/// レイヤ (XCの種類) ごとの規模と利用状況を出力する
+///
+/// TRANSITSは現用パスが中継ノードで当該レイヤのXCを通過した回数，
+/// PATHSは当該レイヤを一度でも中継に用いた現用パスの数．
+fn save_layer_stats(output_dir: &str, network: &Network, demand_list: &[Demand]) {
+    let fiber_breakdown = network.get_fiber_breakdown();
+    let nodes = network.get_nodes();
+
+    let mut f = get_mut_file(&format!("{output_dir}/layer_stats.txt"));
+    writeln!(f, "LAYER XC_COUNT PORTS FIBERS TRANSITS AVG_TRANSITS PATHS").unwrap();
+    for xc_type in XCType::iter() {
+        let scales: Vec<usize> = nodes
+            .iter()
+            .filter_map(|node| network.get_xc_on_node((*node).into(), &xc_type))
+            .map(|xc| xc.get_size())
+            .filter(|&size| size > 0)
+            .collect();
+
+        let fiber_count: usize = fiber_breakdown
+            .iter()
+            .filter(|(sd_xc_type, _)| sd_xc_type.contains(&xc_type))
+            .map(|(_, count)| count)
+            .sum();
+
+        let mut transits = 0;
+        let mut paths = 0;
+        for demand in demand_list {
+            let count = demand.fiber_ids
+                .iter()
+                .rev()
+                .skip(1)
+                .filter(|fiber_id| network.get_fiber_sd_xc_type_by_id(fiber_id)[1] == xc_type)
+                .count();
+            transits += count;
+            if count > 0 {
+                paths += 1;
+            }
+        }
+
+        writeln!(
+            f,
+            "{} {} {} {} {} {:.4} {}",
+            xc_type,
+            scales.len(),
+            scales.iter().sum::<usize>(),
+            fiber_count,
+            transits,
+            transits as f64 / demand_list.len().max(1) as f64,
+            paths,
+        ).unwrap();
+    }
+}
+
+fn save_fragmentation(output_dir: &str, network: &Network) {
+    let metrics = analysis::calc_fragmentation_metrics(network);
+
+    let mut f = get_mut_file(&format!("{output_dir}/fragmentation.txt"));
+    writeln!(f, "EXTERNAL_FRAGMENTATION: {:.5}", metrics.external_fragmentation).unwrap();
+    writeln!(f, "ENTROPY_FRAGMENTATION: {:.5}", metrics.entropy_fragmentation).unwrap();
+    writeln!(f, "LARGEST_FREE_BLOCK: {:.2}", metrics.largest_free_block).unwrap();
+    for (wb_index, utilization) in metrics.waveband_utilization.iter().enumerate() {
+        writeln!(f, "WAVEBAND_{}_UTILIZATION: {:.5}", wb_index, utilization).unwrap();
+    }
 }
  
 fn get_mut_file(filepath: &str) -> File {
@@ -712,6 +935,8 @@ fn save_fiber_breakdown(output_dir: &str, network: &Network) {
 }
  
 fn save_mcf_stats(output_dir: &str, network: &Network) {
+    let crosstalk_model = network.get_crosstalk_model();
+
     let mut f = get_mut_file(&format!("{output_dir}/mcf_stats.txt"));
     for edge in &network.edges {
         let fiber_ids_on_edge = network.get_fiber_id_on_edge(edge);
@@ -721,7 +946,8 @@ fn save_mcf_stats(output_dir: &str, network: &Network) {
                 let unused_core_num = network.get_unused_core(fiber).len();
                 let core_num = fiber.get_core_num();
                 let [src_xc_type, dst_xc_type] = network.get_fiber_sd_xc_type(fiber);
-                writeln!(f, "{} {}={} {}/{}", edge, src_xc_type, dst_xc_type, core_num-unused_core_num, core_num).unwrap();
+                let worst_crosstalk_db = crosstalk_model.calc_fiber_worst_crosstalk_db(fiber);
+                writeln!(f, "{} {}={} {}/{} XT: {:.2} dB", edge, src_xc_type, dst_xc_type, core_num-unused_core_num, core_num, worst_crosstalk_db).unwrap();
             }
         }
     }
@@ -776,15 +1002,14 @@ fn save_wxc_port_pass_count(output_dir: &str, network: &Network, demand_list: &[
         for fiber_id in &demand.fiber_ids {
             let [src_type, dst_type] = network.get_fiber_sd_xc_type_by_id(fiber_id);
             
+            // 補助WXCのポートもWXCのポートとして数える
             match src_type{
-                XCType::Wxc  => count += 1,
+                XCType::Wxc | XCType::Added_Wxc => count += 1,
                 XCType::Wbxc | XCType::Fxc | XCType::Sxc => (),
-                XCType::Added_Wxc => todo!(),
             }
             match dst_type{
-                XCType::Wxc  => count += 1,
+                XCType::Wxc | XCType::Added_Wxc => count += 1,
                 XCType::Wbxc | XCType::Fxc | XCType::Sxc => (),
-                XCType::Added_Wxc => todo!(),
             }
         }
  
@@ -805,6 +1030,50 @@ fn save_wxc_port_pass_count(output_dir: &str, network: &Network, demand_list: &[
     writeln!(f, "Ave: {wxc_pass_count_ave:.5}").unwrap();
 }
  
+/// パス保護の統計
+/// 保護されたパス数と，現用・予備パスの使用スロット数 (スロット × ホップ)
+/// 予備容量比 = 予備パスのために確保したスロット数 / 現用パスの使用スロット数
+/// パス保護を行う設定で，独立な経路対が存在せず保護なしで収容されたパス数も出力する
+fn save_protection_stats(config: &Config, output_dir: &str, network: &Network, demand_list: &[Demand]) {
+    let protection_enabled = ProtectionMode::from_config(config) != ProtectionMode::Unprotected;
+    let mut protected_count = 0;
+    let mut unprotected_fallback_count = 0;
+    let mut working_slot_hops = 0;
+    let mut backup_slot_hops = 0;
+    let mut dedicated_spare_slots = 0;
+
+    for demand in demand_list {
+        working_slot_hops += demand.fiber_ids.len() * demand.slot_width;
+        if let Some(backup) = &demand.backup {
+            protected_count += 1;
+            backup_slot_hops += backup.fiber_ids.len() * demand.slot_width;
+            if backup.shared_risk_groups.is_none() {
+                dedicated_spare_slots += backup.fiber_ids.len() * demand.slot_width;
+            }
+        } else if protection_enabled && !demand.fiber_ids.is_empty() {
+            unprotected_fallback_count += 1;
+        }
+    }
+
+    // 共有予備スロットは共有数によらず1スロットとして数える
+    let shared_spare_slots: usize = network
+        .get_fibers()
+        .values()
+        .map(|fiber| fiber.backup_reservations.len())
+        .sum();
+    let spare_slots = dedicated_spare_slots + shared_spare_slots;
+
+    let mut f = get_mut_file(&format!("{output_dir}/protection_stats.txt"));
+    writeln!(f, "PROTECTED: {}/{}", protected_count, demand_list.len()).unwrap();
+    writeln!(f, "UNPROTECTED_FALLBACK: {}", unprotected_fallback_count).unwrap();
+    writeln!(f, "WORKING_SLOT_HOPS: {}", working_slot_hops).unwrap();
+    writeln!(f, "BACKUP_SLOT_HOPS: {}", backup_slot_hops).unwrap();
+    writeln!(f, "SPARE_SLOTS: {}", spare_slots).unwrap();
+    // 割り当てられたパスがなければ予備容量もない
+    let spare_capacity_ratio = if working_slot_hops == 0 { 0.0 } else { spare_slots as f64 / working_slot_hops as f64 };
+    writeln!(f, "SPARE_CAPACITY_RATIO: {:.5}", spare_capacity_ratio).unwrap();
+}
+ 
 fn save_path_info(output_dir: &str, network: &Network, demand_list: &[Demand]) {
  
     // 0. 各パスの長さ (ホップ数)
@@ -872,6 +1141,47 @@ pub fn save_xc_scale(output_dir: &str, network: &Network) {
     }
 }
  
+/// 局所探索の各反復の近傍操作と目的関数値を出力する
+pub fn save_search_history(output_dir: &str, history: &[SearchStep]) {
+    let mut f = get_mut_file(&format!("{output_dir}/search_history.txt"));
+    writeln!(f, "ITERATION MOVE BYPASSES OBJECTIVE FEASIBLE ACCEPTED BEST").unwrap();
+    for step in history {
+        writeln!(
+            f,
+            "{} {} {} {:.5} {} {} {:.5}",
+            step.iteration,
+            step.search_move,
+            step.bypass_count,
+            step.objective,
+            step.feasible,
+            step.accepted,
+            step.best_objective,
+        ).unwrap();
+    }
+}
+
+/// デフラグメンテーションの有無による呼損率と，再割当の回数を出力する
+pub fn save_defrag_stats(output_dir: &str, results: &[DefragComparison]) {
+    let mut f = get_mut_file(&format!("{output_dir}/defrag_stats.txt"));
+    writeln!(f, "TI BLOCKING_WITHOUT BLOCKING_WITH RUNS MOVES RESCUED EXT_FRAG_WITHOUT EXT_FRAG_WITH ENT_FRAG_WITHOUT ENT_FRAG_WITH").unwrap();
+    for result in results {
+        writeln!(
+            f,
+            "{:.2} {:.5} {:.5} {} {} {} {:.5} {:.5} {:.5} {:.5}",
+            result.traffic_intensity,
+            result.blocking_rate_without,
+            result.blocking_rate_with,
+            result.stats.runs,
+            result.stats.moves,
+            result.stats.rescued,
+            result.fragmentation_without.external_fragmentation,
+            result.fragmentation_with.external_fragmentation,
+            result.fragmentation_without.entropy_fragmentation,
+            result.fragmentation_with.entropy_fragmentation
+        ).unwrap();
+    }
+}
+
 pub fn save_blocking_curve(config: &Config, output_dir: &str, x_y1: &[(f64, f64)], x_y2: &[(f64, f64)]) {
     let target_y = 1e-3;
     let x_for_y1 = find_x_for_y(x_y1, target_y);
@@ -1,11 +1,14 @@
-use assignemnt_instruction::AssignmentInstruction;
+pub use assignemnt_instruction::AssignmentInstruction;
 
 use crate::{
-    config::Config, demand::Demand, network::{ CoreIndex, FiberID, Network, XCType }, np_core::StateMatrix, topology::{ RouteCandidate, Topology }, utils::contains_subslice, WBIndex
+    config::Config, demand::Demand, network::{ protection::{disjoint_type_from_config, ProtectionMode, RiskGroup}, CoreIndex, FiberID, Network, XCType }, np_core::StateMatrix, topology::{ RouteCandidate, Topology }, utils::contains_subslice, WBIndex
 };
 
+use super::ctrl_utils::get_protected_route_pair;
+
 mod assignemnt_instruction;
 mod ff;
+mod protected;
 mod ff_randomized;
 mod rd;
 mod rd_da;
@@ -19,6 +22,21 @@ pub fn search(
     demand: &Demand,
     topology: &Topology,
     network: &mut Network
+) -> Option<AssignmentInstruction> {
+    // パス保護を行う場合は，ルーティングポリシーによらず独立な経路対から探索する
+    if let Some(route_pair) = get_protected_route_pair(config, topology, demand) {
+        return protected::search(demand, topology, network, route_pair, disjoint_type_from_config(config), ProtectionMode::from_config(config));
+    }
+
+    search_unprotected(config, demand, topology, network)
+}
+
+/// パス保護の設定によらず，現用パスのみを探索する
+pub fn search_unprotected(
+    config: &Config,
+    demand: &Demand,
+    topology: &Topology,
+    network: &mut Network
 ) -> Option<AssignmentInstruction> {
     match config.policy.routing_policy.as_str() {
         "FF" | "ff"       =>            ff::search(demand, topology, network),
@@ -55,17 +73,44 @@ pub fn calc_fiber_route_score(network: &Network, fiber_route: &[FiberID]) -> usi
     score
 }
 
+/// 探索時のスロット状態の見え方
+#[derive(Debug, Clone, Copy)]
+enum StateView<'a> {
+    /// 実際の割当状態
+    Actual,
+    /// 共有予備パスの探索時，保護対象のリスクグループと独立な予約済みスロットを空きとみなす
+    SharedBackup(&'a [RiskGroup]),
+}
+
+fn get_state_matrix(network: &Network, fiber_id: &FiberID, core_index: usize, view: StateView) -> StateMatrix {
+    let fiber = network.get_fiber_by_id(fiber_id);
+    match view {
+        StateView::Actual => fiber.state_matrixes[core_index],
+        StateView::SharedBackup(risk_groups) => fiber.get_shareable_state_matrix(&CoreIndex::new(core_index), risk_groups),
+    }
+}
+
 fn get_result_from_route_cand(network: &Network, route_cand: &RouteCandidate) -> Option<AssignmentInstruction> {
-    let fiber_core_route_cands: Vec<(Vec<FiberID>, Vec<CoreIndex>)> = get_empty_fiber_core_routes(network, route_cand, 1);
+    get_result_from_route_cand_with_view(network, route_cand, StateView::Actual).map(|(result, _)| result)
+}
+
+/// 共有予備パスを探索する
+/// 共有するスロット数 (ホップ数) が最大となるスロットを選択し，割当と共有ホップ数を返す
+fn search_shared_backup(network: &Network, route_cand: &RouteCandidate, risk_groups: &[RiskGroup]) -> Option<(AssignmentInstruction, usize)> {
+    get_result_from_route_cand_with_view(network, route_cand, StateView::SharedBackup(risk_groups))
+}
+
+fn get_result_from_route_cand_with_view(network: &Network, route_cand: &RouteCandidate, view: StateView) -> Option<(AssignmentInstruction, usize)> {
+    let fiber_core_route_cands: Vec<(Vec<FiberID>, Vec<CoreIndex>)> = get_empty_fiber_core_routes_with_view(network, route_cand, 1, view);
 
     #[allow(clippy::never_loop)]
     for (fiber_route, core_indices) in &fiber_core_route_cands {
         let mut target_state_matrix = StateMatrix::new();
         let mut flag = true;
+        let mut rejected_by_crosstalk = false;
 
         for (fiber_id, core_index) in fiber_route.iter().zip(core_indices.iter()) {
-            let fiber = network.get_fiber_by_id(fiber_id);
-            let state_matrix_of_fiber_core = fiber.state_matrixes[core_index.index()];
+            let state_matrix_of_fiber_core = get_state_matrix(network, fiber_id, core_index.index(), view);
             // println!("STATEMAT| {target_state_matrix}");
 
             target_state_matrix |= state_matrix_of_fiber_core;
@@ -77,7 +122,9 @@ fn get_result_from_route_cand(network: &Network, route_cand: &RouteCandidate) ->
         }
 
         if flag {
-            
+            // (スロット, 共有ホップ数)
+            let mut best_shared_slot: Option<(usize, usize)> = None;
+
             'slot_loop: for (slot, s) in target_state_matrix.iter().enumerate() {
                 if !*s {
                     for target_fiber_id in fiber_route.iter() {
@@ -93,17 +140,56 @@ fn get_result_from_route_cand(network: &Network, route_cand: &RouteCandidate) ->
                         }
                     }
 
-                    return Some(
+                    if let StateView::SharedBackup(_) = view {
+                        // 予備パスは故障時まで信号を伝送しないため，クロストーク判定は行わない
+                        let shared_hops = fiber_route
+                            .iter()
+                            .zip(core_indices.iter())
+                            .filter(|(fiber_id, core_index)| network.get_fiber_by_id(fiber_id).state_matrixes[core_index.index()][slot])
+                            .count();
+                        if best_shared_slot.is_none_or(|(_, best_shared_hops)| best_shared_hops < shared_hops) {
+                            best_shared_slot = Some((slot, shared_hops));
+                        }
+                        continue 'slot_loop;
+                    }
+
+                    // MCFのコア間クロストークによる受付判定
+                    if !network.is_crosstalk_admissible(fiber_route, core_indices, slot) {
+                        rejected_by_crosstalk = true;
+                        continue 'slot_loop;
+                    }
+
+                    return Some((
                         AssignmentInstruction {
                             fiber_ids: fiber_route.clone(),
                             slot_head: vec![slot; fiber_route.len()],
                             slot_width: 1,
                             core_indices: core_indices.clone(),
-                        }
-                    );
+                            backup: None,
+                        },
+                        0
+                    ));
                 }
 
                 }
+
+            if let StateView::SharedBackup(_) = view {
+                return best_shared_slot.map(|(slot, shared_hops)| (
+                    AssignmentInstruction {
+                        fiber_ids: fiber_route.clone(),
+                        slot_head: vec![slot; fiber_route.len()],
+                        slot_width: 1,
+                        core_indices: core_indices.clone(),
+                        backup: None,
+                    },
+                    shared_hops
+                ));
+            }
+            }
+
+            // 空きスロットはあるが，すべてクロストークにより棄却された
+            if rejected_by_crosstalk {
+                return None;
             }
             panic!();
         }
@@ -115,6 +201,15 @@ fn get_empty_fiber_core_routes(
     network: &Network,
     route_cand: &RouteCandidate,
     width: usize
+) -> Vec<(Vec<FiberID>, Vec<CoreIndex>)> {
+    get_empty_fiber_core_routes_with_view(network, route_cand, width, StateView::Actual)
+}
+
+fn get_empty_fiber_core_routes_with_view(
+    network: &Network,
+    route_cand: &RouteCandidate,
+    width: usize,
+    view: StateView,
 ) -> Vec<(Vec<FiberID>, Vec<CoreIndex>)> {
     // Final result of this function
     let mut result_fiber_core_routes: Vec<(Vec<FiberID>, Vec<CoreIndex>)> = vec![];
@@ -124,13 +219,29 @@ fn get_empty_fiber_core_routes(
     let mut target_core_indices: Vec<CoreIndex> = vec![];
     let mut target_state_matrix: StateMatrix = StateMatrix::new();
 
+    // 故障中のリンクを通過する経路は使用できない
+    if route_cand.edge_route.iter().any(|edge| network.is_edge_failed(edge)) {
+        return result_fiber_core_routes;
+    }
+
     // Get fiber_ids on edges on the route
     let mut fiber_ids_on_edges = vec![];
     for edge in &route_cand.edge_route {
-        fiber_ids_on_edges.push(network.get_fiber_ids_on_edge_empty(edge));
+        match view {
+            StateView::Actual => fiber_ids_on_edges.push(network.get_fiber_ids_on_edge_empty(edge)),
+            // 空きのないファイバにも共有可能なスロットが存在しうる
+            StateView::SharedBackup(_) => fiber_ids_on_edges.push(network.get_fiber_id_on_edge(edge)),
+        }
         // fiber_ids_on_edges.push(network.get_fiber_id_on_edge_partial(edge));
     }
 
+    // 故障したXCに接続されたファイバは使用できない
+    if network.has_failed_xcs() {
+        for fiber_ids in fiber_ids_on_edges.iter_mut() {
+            fiber_ids.retain(|fiber_id| !network.is_fiber_failed(fiber_id));
+        }
+    }
+
     let _ = get_empty_fiber_core_routes_recursive(
         network,
         &fiber_ids_on_edges,
@@ -138,7 +249,8 @@ fn get_empty_fiber_core_routes(
         &mut result_fiber_core_routes,
         &mut target_fiber_route,
         &mut target_state_matrix,
-        &mut target_core_indices
+        &mut target_core_indices,
+        view,
     );
 
     if !SHORTCUT {
@@ -162,6 +274,7 @@ fn get_empty_fiber_core_routes(
     result_fiber_core_routes
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_empty_fiber_core_routes_recursive(
     network: &Network,
     fiber_ids_on_edges: &[Vec<FiberID>],
@@ -170,6 +283,7 @@ fn get_empty_fiber_core_routes_recursive(
     target_fiber_route: &mut Vec<FiberID>,
     target_state_matrix: &mut StateMatrix,
     target_core_indices: &mut Vec<CoreIndex>,
+    view: StateView,
 ) -> Result<(), ()> {
     let target_level = target_fiber_route.len();
     let final_level = fiber_ids_on_edges.len() - 1;
@@ -185,7 +299,8 @@ fn get_empty_fiber_core_routes_recursive(
             // Check first XC type
             let first_fiber = network.get_fiber_by_id(target_fiber_id);
             let first_xc_type = network.get_fiber_sd_xc_type(first_fiber)[0];
-            if first_xc_type != XCType::Wxc {
+            // 補助WXCは同一ノードのWXCから入力されるため，始端として扱える
+            if first_xc_type != XCType::Wxc && first_xc_type != XCType::Added_Wxc {
                 continue; // To next fiber
             }
         }
@@ -213,7 +328,7 @@ fn get_empty_fiber_core_routes_recursive(
                     let target_core_index: CoreIndex = target_core_index_as_usize.into();
 
                     // Mask Slots
-                    let state_matrix_of_target_fiber_core = network.get_fiber_by_id(target_fiber_id).state_matrixes[target_core_index_as_usize];
+                    let state_matrix_of_target_fiber_core = get_state_matrix(network, target_fiber_id, target_core_index_as_usize, view);
                     let new_target_state_matrix = *target_state_matrix | state_matrix_of_target_fiber_core;
                     let tmp_target_state_matrix = *target_state_matrix;
 
@@ -240,7 +355,7 @@ fn get_empty_fiber_core_routes_recursive(
                                 continue;
                             }
 
-                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices) {
+                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices, view) {
                                 Ok(_) => {
     
                                     if SHORTCUT { return Ok(()) }
@@ -275,7 +390,7 @@ fn get_empty_fiber_core_routes_recursive(
 
                 {
                     // Mask Slots
-                    let state_matrix_of_target_fiber_core = network.get_fiber_by_id(target_fiber_id).state_matrixes[target_core_index.index()];
+                    let state_matrix_of_target_fiber_core = get_state_matrix(network, target_fiber_id, target_core_index.index(), view);
                     let new_target_state_matrix = *target_state_matrix | state_matrix_of_target_fiber_core;
                     let tmp_target_state_matrix = *target_state_matrix;
 
@@ -302,7 +417,7 @@ fn get_empty_fiber_core_routes_recursive(
                                 continue;
                             }
 
-                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices) {
+                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices, view) {
                                 Ok(_) => {
 
                                     if SHORTCUT { return Ok(()) }
@@ -337,7 +452,7 @@ fn get_empty_fiber_core_routes_recursive(
                     }
 
                     // Mask Slots
-                    let state_matrix_of_target_fiber_core = network.get_fiber_by_id(target_fiber_id).state_matrixes[0];
+                    let state_matrix_of_target_fiber_core = get_state_matrix(network, target_fiber_id, 0, view);
                     let mut new_target_state_matrix = *target_state_matrix | state_matrix_of_target_fiber_core;
                     new_target_state_matrix.apply_witout_wb_filter(&wb_index);
                     let tmp_target_state_matrix = *target_state_matrix;
@@ -366,7 +481,7 @@ fn get_empty_fiber_core_routes_recursive(
                                 continue;
                             }
 
-                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices) {
+                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices, view) {
                                 Ok(_) => {
                                     if SHORTCUT { return Ok(()) }
 
@@ -396,7 +511,7 @@ fn get_empty_fiber_core_routes_recursive(
                     }
 
                     // Mask Slots
-                    let state_matrix_of_target_fiber_core = network.get_fiber_by_id(target_fiber_id).state_matrixes[0];
+                    let state_matrix_of_target_fiber_core = get_state_matrix(network, target_fiber_id, 0, view);
                     let mut new_target_state_matrix = *target_state_matrix | state_matrix_of_target_fiber_core;
                     new_target_state_matrix.apply_witout_wb_filter(&wb_index);
                     let tmp_target_state_matrix = *target_state_matrix;
@@ -425,7 +540,7 @@ fn get_empty_fiber_core_routes_recursive(
                                 continue;
                             }
 
-                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices) {
+                            match get_empty_fiber_core_routes_recursive(network, fiber_ids_on_edges, width, result_fiber_core_routes, target_fiber_route, target_state_matrix, target_core_indices, view) {
                                 Ok(_) => {
                                     if SHORTCUT { return Ok(()) }
 
@@ -493,7 +608,11 @@ fn check_continuity(network: &Network, target_fiber_route: &[FiberID], target_fi
         // Check matching of xc id
         let input_parent_xc  = network.get_xc_by_input_port_id(&input_ids[target_core_index.index()]);
         let output_parent_xc = network.get_xc_by_output_port_id(&output_ids[target_core_index.index()]);
-        if input_parent_xc.id != output_parent_xc.id {
+        // 補助WXCへは同一ノードのWXCから接続できる
+        let is_added_wxc_entry = input_parent_xc.xc_type == XCType::Wxc
+            && output_parent_xc.xc_type == XCType::Added_Wxc
+            && input_parent_xc.node == output_parent_xc.node;
+        if input_parent_xc.id != output_parent_xc.id && !is_added_wxc_entry {
             return false;
         }
 
@@ -1,8 +1,9 @@
-use crate::network::{CoreIndex, FiberID};
+use crate::network::{protection::BackupPath, CoreIndex, FiberID};
 
 pub struct AssignmentInstruction {
     pub fiber_ids: Vec<FiberID>,
     pub core_indices: Vec<CoreIndex>,
     pub slot_head: Vec<usize>,
-    pub slot_width: usize
+    pub slot_width: usize,
+    pub backup: Option<BackupPath>,
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use crate::{
+    demand::Demand,
+    network::{ protection::{ get_equipment_risk_groups, get_risk_groups, BackupPath, ProtectionMode }, Network },
+    topology::{ DisjointRoutePair, DisjointType, RouteCandidate, Topology },
+};
+
+use super::{assignemnt_instruction::AssignmentInstruction, get_result_from_route_cand, search_shared_backup};
+
+/// 現用パスと予備パスを同時に探索する
+///
+/// Suurballeの経路対を最初に試し，その後は経路候補の順に，各候補と独立な (SRLGも共有しない) 候補を予備経路とする．
+/// 専用パス保護では最初に見つかった経路対を，共有予備パス保護では
+/// 現用パスごとに新たに必要となる予備スロット数 (ホップ数 - 共有ホップ数) が最小の予備経路を選択する．
+pub fn search(
+    demand: &Demand,
+    topology: &Topology,
+    network: &Network,
+    route_pair: &DisjointRoutePair,
+    disjoint_type: DisjointType,
+    protection_mode: ProtectionMode,
+) -> Option<AssignmentInstruction> {
+    let route_cands = topology.route_candidates.get(&demand.sd).unwrap();
+
+    let mut working_cands: Vec<(&RouteCandidate, Vec<&RouteCandidate>)> = vec![(&route_pair.primary, vec![&route_pair.secondary])];
+    for working_cand in route_cands {
+        let backup_cands: Vec<&RouteCandidate> = route_cands
+            .iter()
+            .filter(|x| working_cand.is_disjoint_with(x, disjoint_type) && topology.is_srlg_disjoint(working_cand, x))
+            .collect();
+        if !backup_cands.is_empty() {
+            working_cands.push((working_cand, backup_cands));
+        }
+    }
+
+    for (working_cand, backup_cands) in working_cands {
+        // 経路対は独立であるため，ファイバを共有せず個別に探索できる
+        let Some(mut working) = get_result_from_route_cand(network, working_cand) else {
+            continue;
+        };
+
+        let backup = match protection_mode {
+            ProtectionMode::Unprotected => unreachable!(),
+            ProtectionMode::Dedicated => {
+                // 専用パス保護では最初に見つかった予備経路を用いる
+                get_result_from_route_cand(network, backup_cands[0]).map(|backup| BackupPath {
+                    fiber_ids: backup.fiber_ids,
+                    slot_heads: backup.slot_head,
+                    core_indices: backup.core_indices,
+                    shared_risk_groups: None,
+                })
+            }
+            ProtectionMode::Shared => {
+                // ノード・XCの故障で同時に切り替わる予備パスとも，スロットを共有しない
+                let mut risk_groups = get_risk_groups(topology, &working_cand.edge_route);
+                risk_groups.extend(get_equipment_risk_groups(network, &working.fiber_ids));
+
+                let mut best_backup: Option<(AssignmentInstruction, usize)> = None;
+                for backup_cand in backup_cands {
+                    if let Some((backup, shared_hops)) = search_shared_backup(network, backup_cand, &risk_groups) {
+                        let new_slot_hops = backup.fiber_ids.len() - shared_hops;
+                        if best_backup.as_ref().is_none_or(|(_, best_new_slot_hops)| new_slot_hops < *best_new_slot_hops) {
+                            best_backup = Some((backup, new_slot_hops));
+                        }
+                    }
+                }
+
+                best_backup.map(|(backup, _)| BackupPath {
+                    fiber_ids: backup.fiber_ids,
+                    slot_heads: backup.slot_head,
+                    core_indices: backup.core_indices,
+                    shared_risk_groups: Some(risk_groups),
+                })
+            }
+        };
+
+        if backup.is_some() {
+            working.backup = backup;
+            return Some(working);
+        }
+    }
+
+    None
+}
@@ -81,6 +81,7 @@ pub fn search(
                         slot_head: vec![slot; fiber_route.len()],
                         slot_width: width,
                         core_indices: vec![CoreIndex::new(0); fiber_route.len()],
+                        backup: None,
                     }
                 });
             }
@@ -106,6 +106,19 @@ pub fn log_fiber_remove(config: &Config, fiber: &Fiber) {
     }
 }
 
+/// NWの不変条件を検証し，違反があれば一覧を出力して停止する
+pub fn validate_network(config: &Config, network: &Network, demand_list: &[Demand]) {
+    if config.debug.validate_network {
+        let violations = network.validate(demand_list);
+        if !violations.is_empty() {
+            for violation in &violations {
+                println!("{:>8}| {}", style("INVALID").red(), violation);
+            }
+            panic!("The network has {} invariant violations", violations.len());
+        }
+    }
+}
+
 pub fn log_taboo_list_addition(config: &Config, sd: &SD) {
     if config.debug.log_taboo {
         println!("{:>8}| {}", style("TABOO").magenta(), sd);
@@ -210,6 +223,20 @@ pub fn log_state_matrix(config: &Config, network: &Network) {
     // }
 }
 
+pub fn log_fragmentation(config: &Config, arrival: usize, metrics: &analysis::FragmentationMetrics) {
+    if config.debug.log_fragmentation {
+        println!(
+            "{:>8}| {:6} EXT: {:.3} ENT: {:.3} LFB: {:5.1} WB: {:.3?}",
+            style("FRAG").cyan(),
+            arrival,
+            metrics.external_fragmentation,
+            metrics.entropy_fragmentation,
+            metrics.largest_free_block,
+            metrics.waveband_utilization
+        );
+    }
+}
+
 #[allow(dead_code)]
 pub fn log_state_matrix_wo_w2w(config: &Config, network: &Network) {
     if config.debug.log_state_matrix {
@@ -1,4 +1,18 @@
-use crate::network::{Network, XCType };
+use crate::{
+    network::{Network, XCType },
+    np_core::{parameters::{SLOT, WAVEBAND_COUNT}, StateMatrix},
+    WBIndex,
+};
+
+/// 指定した種類のXCのうち，最大のポート数
+pub fn calc_max_xc_size(network: &Network, xc_type: &XCType) -> usize {
+    network.xcs
+        .values()
+        .filter(|xc| xc.xc_type == *xc_type)
+        .map(|xc| xc.get_size())
+        .max()
+        .unwrap_or(0)
+}
 
 pub fn calc_fiber_count_ratio(network: &Network, conv_nw_w2w_fiber_count: usize) -> f64 {
 
@@ -34,3 +48,166 @@ pub fn calc_max_wxc_size(network: &Network) -> usize {
     wxc_scale
 }
 
+
+/// スペクトルの断片化指標 (ファイバ・コアごとの値の平均)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FragmentationMetrics {
+    /// 外部断片化率 (1 - 最大空きブロック / 空きスロット数)
+    pub external_fragmentation: f64,
+    /// エントロピーに基づく断片化指標
+    pub entropy_fragmentation: f64,
+    /// 最大空きブロックのスロット数
+    pub largest_free_block: f64,
+    /// WBXCに接続されたファイバの波長帯ごとの使用率 (該当ファイバがない場合は0)
+    pub waveband_utilization: [f64; WAVEBAND_COUNT],
+}
+
+/// 複数時点の断片化指標の平均
+pub fn average_fragmentation_metrics(samples: &[FragmentationMetrics]) -> FragmentationMetrics {
+    let mut average = FragmentationMetrics::default();
+    if samples.is_empty() {
+        return average;
+    }
+
+    for sample in samples {
+        average.external_fragmentation += sample.external_fragmentation;
+        average.entropy_fragmentation += sample.entropy_fragmentation;
+        average.largest_free_block += sample.largest_free_block;
+        for (sum, utilization) in average.waveband_utilization.iter_mut().zip(sample.waveband_utilization) {
+            *sum += utilization;
+        }
+    }
+
+    let n = samples.len() as f64;
+    average.external_fragmentation /= n;
+    average.entropy_fragmentation /= n;
+    average.largest_free_block /= n;
+    for utilization in average.waveband_utilization.iter_mut() {
+        *utilization /= n;
+    }
+
+    average
+}
+
+/// 連続する空きスロットのブロック長
+fn get_free_blocks(state_matrix: &StateMatrix) -> Vec<usize> {
+    let mut blocks = vec![];
+    let mut length = 0;
+    for &s in state_matrix.iter() {
+        if s {
+            if length > 0 {
+                blocks.push(length);
+            }
+            length = 0;
+        } else {
+            length += 1;
+        }
+    }
+    if length > 0 {
+        blocks.push(length);
+    }
+    blocks
+}
+
+pub fn calc_largest_free_block(state_matrix: &StateMatrix) -> usize {
+    get_free_blocks(state_matrix).into_iter().max().unwrap_or(0)
+}
+
+/// 外部断片化率
+/// 空きスロットが全て連続していれば0，空きスロットがない場合も0
+pub fn calc_external_fragmentation(state_matrix: &StateMatrix) -> f64 {
+    let free_blocks = get_free_blocks(state_matrix);
+    let free_slots: usize = free_blocks.iter().sum();
+    if free_slots == 0 {
+        return 0.0;
+    }
+
+    1.0 - (*free_blocks.iter().max().unwrap() as f64) / (free_slots as f64)
+}
+
+/// エントロピーに基づく断片化指標
+/// 空きブロック長 D_i と全スロット数 D に対し -Σ (D_i / D) ln(D_i / D)
+pub fn calc_entropy_fragmentation(state_matrix: &StateMatrix) -> f64 {
+    get_free_blocks(state_matrix)
+        .into_iter()
+        .map(|block| {
+            let p = block as f64 / SLOT as f64;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// 波長帯ごとの使用率
+pub fn calc_waveband_utilization(state_matrix: &StateMatrix) -> [f64; WAVEBAND_COUNT] {
+    let mut utilization = [0.0; WAVEBAND_COUNT];
+    for wb_index in WBIndex::iter() {
+        let used = state_matrix
+            .iter()
+            .enumerate()
+            .filter(|(slot, s)| **s && wb_index.includes(*slot))
+            .count();
+        utilization[wb_index.index()] = used as f64 / (SLOT / WAVEBAND_COUNT) as f64;
+    }
+    utilization
+}
+
+/// Network全体の断片化指標
+pub fn calc_fragmentation_metrics(network: &Network) -> FragmentationMetrics {
+    let mut metrics = FragmentationMetrics::default();
+
+    let mut core_count = 0;
+    let mut wb_core_count = 0;
+    for fiber in network.get_fibers().values() {
+        let is_wb_fiber = fiber.sd_xc_type.contains(&XCType::Wbxc);
+
+        for state_matrix in &fiber.state_matrixes {
+            metrics.external_fragmentation += calc_external_fragmentation(state_matrix);
+            metrics.entropy_fragmentation += calc_entropy_fragmentation(state_matrix);
+            metrics.largest_free_block += calc_largest_free_block(state_matrix) as f64;
+            core_count += 1;
+
+            if is_wb_fiber {
+                for (sum, utilization) in metrics.waveband_utilization.iter_mut().zip(calc_waveband_utilization(state_matrix)) {
+                    *sum += utilization;
+                }
+                wb_core_count += 1;
+            }
+        }
+    }
+
+    if core_count > 0 {
+        metrics.external_fragmentation /= core_count as f64;
+        metrics.entropy_fragmentation /= core_count as f64;
+        metrics.largest_free_block /= core_count as f64;
+    }
+    if wb_core_count > 0 {
+        for utilization in metrics.waveband_utilization.iter_mut() {
+            *utilization /= wb_core_count as f64;
+        }
+    }
+
+    metrics
+}
+
+#[test]
+fn fragmentation_metrics_test() {
+    let mut state_matrix = StateMatrix::new();
+    assert_eq!(calc_largest_free_block(&state_matrix), SLOT);
+    assert_eq!(calc_external_fragmentation(&state_matrix), 0.0);
+    assert_eq!(calc_entropy_fragmentation(&state_matrix), 0.0);
+
+    // 空きブロック: [0, 10), [11, SLOT)
+    state_matrix[10] = true;
+    assert_eq!(calc_largest_free_block(&state_matrix), SLOT - 11);
+    let free_slots = (SLOT - 1) as f64;
+    assert!((calc_external_fragmentation(&state_matrix) - (1.0 - (SLOT - 11) as f64 / free_slots)).abs() < 1e-9);
+    assert!(calc_entropy_fragmentation(&state_matrix) > 0.0);
+
+    let utilization = calc_waveband_utilization(&state_matrix);
+    assert!((utilization[WBIndex::from_wavelength(10).index()] - 1.0 / (SLOT / WAVEBAND_COUNT) as f64).abs() < 1e-9);
+    assert_eq!(utilization.iter().filter(|&&x| x == 0.0).count(), WAVEBAND_COUNT - 1);
+
+    let full = StateMatrix::new_fulfilled();
+    assert_eq!(calc_largest_free_block(&full), 0);
+    assert_eq!(calc_external_fragmentation(&full), 0.0);
+}
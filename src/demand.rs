@@ -1,7 +1,8 @@
 use std::{ fs::File, io::Read };
+use serde_derive::{ Deserialize, Serialize };
 
 use crate::{
-    config::Config, network::{CoreIndex, FiberID}, np_core::dist::get_poisson_interval, topology::{ get_ave_shortest_hops, Topology }, SD, SLOT
+    config::Config, network::{protection::BackupPath, CoreIndex, FiberID}, np_core::dist::get_poisson_interval, topology::{ get_ave_shortest_hops, Topology }, SD, SLOT
 };
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
@@ -10,7 +11,7 @@ pub mod dynamic;
 
 const LAMBDA_C: f64 = 1.0 / 3000.0;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Demand {
     pub sd: SD,
     pub fiber_ids: Vec<FiberID>,
@@ -22,6 +23,8 @@ pub struct Demand {
     pub end_time: usize,
     pub duration: usize,
     pub data_speed: usize, // Gbps
+    /// 予備パス (パス保護を行う場合)
+    pub backup: Option<BackupPath>,
 }
 
 #[derive(Debug)]
@@ -39,6 +42,16 @@ impl Demand {
         self.slot_heads.clear();
         self.slot_width = 0;
         self.core_indices.clear();
+        self.backup = None;
+    }
+
+    /// 現用パスと予備パスのファイバルート
+    pub fn get_fiber_routes(&self) -> Vec<&[FiberID]> {
+        let mut routes: Vec<&[FiberID]> = vec![&self.fiber_ids];
+        if let Some(backup) = &self.backup {
+            routes.push(&backup.fiber_ids);
+        }
+        routes
     }
 
     pub fn new(sd: SD, index: usize, start: usize, duration: usize) -> Self {
@@ -54,6 +67,7 @@ impl Demand {
             data_speed: 0,
             slot_width: 0,
             core_indices: vec![],
+            backup: None,
         }
     }
 }
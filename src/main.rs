@@ -15,7 +15,7 @@ mod np_core;
 mod topology;
 mod utils;
 
-use controller::{dynamic::get_blocking_curve, output::{self, save_blocking_curve}};
+use controller::{dynamic::{get_blocking_curve, get_defrag_comparison, DefragTrigger}, output::{self, save_blocking_curve, save_defrag_stats}};
 use network::{network_from_hashmap, wxc_network_from_hashmap};
 pub use np_core::{ Edge, Node, SD, WBIndex };
 
@@ -34,15 +34,31 @@ fn main() {
     }
 
     let args: Vec<String> = env::args().collect();
-    let mut config = if args.len() == 2 {
-        config::Config::new(&args[1])
+    let config = if args.len() == 3 && args[1] == "--resume" {
+        // 中断した設計を，出力先ディレクトリに保存された設定とチェックポイントから再開する
+        // 保存された`outdir`は起動時のディレクトリからの相対パスのため，同じディレクトリで起動すること
+        let mut config = config::Config::new(&format!("{}/config.toml", args[2]));
+        config.simulation.resume = true;
+        config
     } else {
-        config::Config::new("./config.toml")
+        let mut config = if args.len() == 2 {
+            config::Config::new(&args[1])
+        } else {
+            config::Config::new("./config.toml")
+        };
+        output::init_master_dir(&mut config);
+        config
     };
-    output::init_master_dir(&mut config);
 
     let (network, _topology, _specific_outdir) = controller::main(&config);
 
+    // 動的解析におけるデフラグメンテーションの効果
+    if DefragTrigger::from_config(&config) != DefragTrigger::Disabled {
+        // 設計後のNetworkはパスが全て削除され，ファイバのみが残っている
+        let defrag_results = get_defrag_comparison(&config, &network, &_topology, &[config.simulation.traffic_intensity]);
+        save_defrag_stats(&_specific_outdir, &defrag_results);
+    }
+
     // {
     //     let tis: Vec<f64> = arange(
     //         config.simulation.traffic_intensity - CURVE_RANGE_BOTTOM,
@@ -11,34 +11,62 @@ use crate::{
     Node, WBIndex, SD,
 };
 use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
+use crosstalk::{CrosstalkModel, WorkingRoute};
 use layer_to_np2::debug_println;
+use arena::Arena;
+use ids::IdCounters;
+use journal::Journal;
 use layer_top::LayerTopology;
+use protection::{BackupPath, RiskGroup};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
+use serde_derive::{ Deserialize, Serialize };
 use strum::IntoEnumIterator;
 pub use xc::PortID;
-use xc::XCID;
 
 use crate::{config::Config, debugger, demand::Demand, topology::Topology, Edge};
 
+mod arena;
+pub mod cost;
+pub mod crosstalk;
+mod ids;
+mod journal;
 pub mod nw_utils;
+pub mod power;
+pub mod protection;
 pub mod state_matrix;
+mod validate;
+pub mod wss;
 
 mod layer_top;
 
 const UPDATE_LAYER_TOPOLOGIES: bool = true;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Network {
-    fibers: FxHashMap<FiberID, Fiber>,
+    fibers: Arena<FiberID, Fiber>,
     fiber_ids_on_edges: FxHashMap<Edge, Vec<FiberID>>,
     pub edges: Vec<Edge>,
-    pub xcs: FxHashMap<XCID, XC>,
+    pub xcs: Arena<XCID, XC>,
+    /// ノードごとのXCの表 (`XCType`の値を添字とする)
+    xc_ids_on_nodes: Vec<[Option<XCID>; XC_TYPE_COUNT]>,
     edge_costs: FxHashMap<Edge, f64>,
     empty_fiber_ids_on_edges_cache: FxHashMap<Edge, Vec<FiberID>>,
     pub rng: ChaCha8Rng,
-    portid_to_xcid: FxHashMap<PortID, XCID>,
+    portid_to_xcid: Arena<PortID, XCID>,
     layer_topologies: FxHashMap<XCType, LayerTopology>,
+    crosstalk_model: CrosstalkModel,
+    /// 収容中の現用パスの経路 (Demandのindex -> 経路)
+    working_routes: FxHashMap<usize, WorkingRoute>,
+    /// 故障中のリンク (両方向)
+    failed_edges: FxHashSet<Edge>,
+    /// 故障中のXC
+    failed_xc_ids: FxHashSet<XCID>,
+    /// ファイバ・XC・ポートのIDの発行元
+    id_counters: IdCounters,
+    /// トランザクション中の変更の記録
+    #[serde(skip)]
+    journal: Option<Box<Journal>>,
 }
 
 impl Display for Network {
@@ -58,26 +86,21 @@ impl Display for Network {
 impl Network {
     pub fn new(config: &Config, topology: &Topology, xc_types: &[XCType]) -> Self {
         // Fibers
-        let fibers: FxHashMap<FiberID, Fiber> = FxHashMap::default();
+        let fibers: Arena<FiberID, Fiber> = Arena::default();
         let fiber_ids_on_edges: FxHashMap<Edge, Vec<FiberID>> = FxHashMap::default();
 
         // For What?
         let edges: Vec<Edge> = topology.edges.clone();
 
         // XCs
-        let mut xcs: FxHashMap<XCID, XC> = FxHashMap::default();
+        let xcs: Arena<XCID, XC> = Arena::default();
         let node_count: usize = topology.link_matrix.len();
-        for node in 0..node_count {
-            for xc_type in xc_types {
-                let xc: XC = XC::new(node, *xc_type);
-                xcs.insert(xc.id, xc);
-            }
-        }
+        let xc_ids_on_nodes = vec![[None; XC_TYPE_COUNT]; node_count];
 
         // For chache
         let edge_costs: FxHashMap<Edge, f64> = FxHashMap::default();
         let empty_fiber_ids_on_edges_cache: FxHashMap<Edge, Vec<FiberID>> = FxHashMap::default();
-        let portid_to_xcid = FxHashMap::default();
+        let portid_to_xcid = Arena::default();
 
         // For new Routerrrr
         let layer_topologies = FxHashMap::default();
@@ -85,18 +108,39 @@ impl Network {
         // For randomize
         let rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(config.simulation.random_seed);
 
+        // For MCF
+        let crosstalk_model = CrosstalkModel::new(config);
+        let working_routes = FxHashMap::default();
+
+        // For failure simulation
+        let failed_edges = FxHashSet::default();
+        let failed_xc_ids = FxHashSet::default();
+
         let mut network = Network {
             fibers,
             fiber_ids_on_edges,
             edges,
             xcs,
+            xc_ids_on_nodes,
             edge_costs,
             empty_fiber_ids_on_edges_cache,
             rng,
             portid_to_xcid,
             layer_topologies,
+            crosstalk_model,
+            working_routes,
+            failed_edges,
+            failed_xc_ids,
+            id_counters: IdCounters::default(),
+            journal: None,
         };
 
+        for node in 0..node_count {
+            for xc_type in xc_types {
+                network.get_xc_mut_on_node(node, xc_type);
+            }
+        }
+
         for &edge in &topology.edges {
             let fiber = generate_new_fiber(&mut network, &edge, XCType::Wxc, XCType::Wxc);
             network
@@ -113,7 +157,7 @@ impl Network {
         network
     }
 
-    pub fn get_all_fibers(&self) -> &FxHashMap<FiberID, Fiber> {
+    pub fn get_all_fibers(&self) -> &Arena<FiberID, Fiber> {
         &self.fibers
     }
 
@@ -245,7 +289,7 @@ impl Network {
     }
 
     /// fibersを返す
-    pub fn get_fibers(&self) -> &FxHashMap<FiberID, Fiber> {
+    pub fn get_fibers(&self) -> &Arena<FiberID, Fiber> {
         &self.fibers
     }
 
@@ -407,7 +451,7 @@ impl Network {
 
                 // TABOO_LISTへの追加
                 let fiber_type = self.get_fiber_sd_xc_type(fiber);
-                if fiber_type == [XCType::Wxc, XCType::Fxc] {
+                if fiber_type == [XCType::Wxc, XCType::Fxc] || fiber_type == [XCType::Added_Wxc, XCType::Fxc] {
                     let seq = self.get_fiber_sequence_as_edges(fiber).unwrap();
                     let sd = SD::new(
                         seq.first().unwrap().src.into(),
@@ -437,6 +481,9 @@ impl Network {
 
         self.fibers.remove(fiber_id);
 
+        self.record_empty_fiber_ids_on_edge(&edge);
+        self.record_fiber_ids_on_edge(&edge);
+
         if fiber_sd_xc_type.contains(&XCType::Sxc) {
             for core_index_as_usize in 0..CORE_FACTOR {
                 let src_xc = self.get_xc_mut_by_output_port_id(&src_device_id[core_index_as_usize]);
@@ -473,6 +520,7 @@ impl Network {
 
     /// IDで指定してファイバ(参照)を取得する
     pub fn get_fiber_mut_by_id(&mut self, fiber_id: &FiberID) -> &mut Fiber {
+        self.record_fiber(fiber_id);
         match self.fibers.get_mut(fiber_id) {
             Some(fiber) => fiber,
             None => panic!("The fiber is not exist."),
@@ -488,6 +536,7 @@ impl Network {
             residual += fiber.residual;
             capacity += fiber.residual + fiber.occupancy;
         }
+        self.record_edge_cost(edge);
         self.edge_costs
             .insert(*edge, (capacity as f64) / ((residual as f64) + 0.01));
     }
@@ -498,24 +547,52 @@ impl Network {
         slots: Vec<usize>,
         target_fiber_ids: &[FiberID],
         core_indices: &[CoreIndex],
+        backup: Option<&BackupPath>,
         demand: &Demand,
     ) {
-        self.assign_path_da(slots, 1, target_fiber_ids, core_indices, demand);
+        self.assign_path_da(slots, 1, target_fiber_ids, core_indices, backup, demand);
     }
 
+    /// 予備パスが指定された場合は，現用パスと合わせて予約する
     pub fn assign_path_da(
         &mut self,
         slots: Vec<usize>,
         width: usize,
         target_fiber_ids: &[FiberID],
         core_indices: &[CoreIndex],
+        backup: Option<&BackupPath>,
         demand: &Demand,
+    ) {
+        self.assign_fiber_route(slots[0], width, target_fiber_ids, core_indices, demand.index);
+        self.record_working_route(demand.index);
+        self.working_routes.insert(demand.index, WorkingRoute {
+            fiber_ids: target_fiber_ids.to_vec(),
+            core_indices: core_indices.to_vec(),
+            slot_head: slots[0],
+            slot_width: width,
+        });
+
+        if let Some(backup) = backup {
+            match &backup.shared_risk_groups {
+                Some(risk_groups) => self.reserve_backup_route(backup.slot_heads[0], width, &backup.fiber_ids, &backup.core_indices, risk_groups, demand.index),
+                None => self.assign_fiber_route(backup.slot_heads[0], width, &backup.fiber_ids, &backup.core_indices, demand.index),
+            }
+        }
+    }
+
+    fn assign_fiber_route(
+        &mut self,
+        slot: usize,
+        width: usize,
+        target_fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        demand_index: usize,
     ) {
         for target_fiber_id in target_fiber_ids.iter() {
             let target_fiber = self.get_fiber_by_id(target_fiber_id);
             if target_fiber.sd_xc_type == [XCType::Wxc, XCType::Wbxc] {
                 let fiber_seq = self
-                    .get_fiber_sequence_wb(target_fiber, &WBIndex::from_wavelength(slots[0]))
+                    .get_fiber_sequence_wb(target_fiber, &WBIndex::from_wavelength(slot))
                     .unwrap();
                 if !contains_subslice(target_fiber_ids, &fiber_seq) {
                     debug_println!(target_fiber_ids);
@@ -538,9 +615,10 @@ impl Network {
         for (target_fiber_id, core_index) in target_fiber_ids.iter().zip(core_indices.iter()) {
             let fiber = self.get_fiber_mut_by_id(target_fiber_id);
             let edge = fiber.edge;
-            fiber.assign(slots[0], width, core_index, demand.index);
+            fiber.assign(slot, width, core_index, demand_index);
 
             if fiber.is_full() {
+                self.record_empty_fiber_ids_on_edge(&edge);
                 self.empty_fiber_ids_on_edges_cache
                     .get_mut(&edge)
                     .unwrap()
@@ -557,8 +635,85 @@ impl Network {
     }
 
     /// 登録済みのDemandをNetworkから削除する
+    /// 予備パスも合わせて解放する
     pub fn remove_path_da(&mut self, demand: &Demand) {
-        for (target_fiber_id, core_index) in demand.fiber_ids.iter().zip(demand.core_indices.iter())
+        self.remove_fiber_route(demand.slot_heads[0], demand.slot_width, &demand.fiber_ids, &demand.core_indices, demand.index);
+        self.record_working_route(demand.index);
+        self.working_routes.remove(&demand.index);
+
+        if let Some(backup) = &demand.backup {
+            match &backup.shared_risk_groups {
+                Some(_) => self.release_backup_route(backup.slot_heads[0], demand.slot_width, &backup.fiber_ids, &backup.core_indices, demand.index),
+                None => self.remove_fiber_route(backup.slot_heads[0], demand.slot_width, &backup.fiber_ids, &backup.core_indices, demand.index),
+            }
+        }
+    }
+
+    /// 共有予備パスのスロットを予約する
+    fn reserve_backup_route(
+        &mut self,
+        slot: usize,
+        width: usize,
+        target_fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        risk_groups: &[RiskGroup],
+        demand_index: usize,
+    ) {
+        for (target_fiber_id, core_index) in target_fiber_ids.iter().zip(core_indices.iter()) {
+            let fiber = self.get_fiber_mut_by_id(target_fiber_id);
+            let edge = fiber.edge;
+            fiber.reserve_backup(slot, width, core_index, demand_index, risk_groups);
+
+            if fiber.is_full() {
+                self.record_empty_fiber_ids_on_edge(&edge);
+                self.empty_fiber_ids_on_edges_cache
+                    .get_mut(&edge)
+                    .unwrap()
+                    .retain(|&x| x != *target_fiber_id);
+            }
+
+            self.calc_edge_cost(&edge);
+        }
+    }
+
+    /// 共有予備パスの予約を解除する
+    fn release_backup_route(
+        &mut self,
+        slot: usize,
+        width: usize,
+        target_fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        demand_index: usize,
+    ) {
+        for (target_fiber_id, core_index) in target_fiber_ids.iter().zip(core_indices.iter()) {
+            let fiber = self.get_fiber_mut_by_id(target_fiber_id);
+            let edge = fiber.edge;
+
+            let fiber_is_full = fiber.is_full();
+            fiber.release_backup(slot, width, core_index, demand_index);
+
+            // 他の予備パスと共有中のスロットは解放されないため，空きができた場合のみキャッシュを更新
+            if fiber_is_full && !fiber.is_full() {
+                self.record_empty_fiber_ids_on_edge(&edge);
+                self.empty_fiber_ids_on_edges_cache
+                    .get_mut(&edge)
+                    .unwrap()
+                    .push(*target_fiber_id);
+            }
+
+            self.calc_edge_cost(&edge);
+        }
+    }
+
+    fn remove_fiber_route(
+        &mut self,
+        slot: usize,
+        width: usize,
+        target_fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        demand_index: usize,
+    ) {
+        for (target_fiber_id, core_index) in target_fiber_ids.iter().zip(core_indices.iter())
         {
             let fiber = self.get_fiber_mut_by_id(target_fiber_id);
             let edge = fiber.edge;
@@ -566,13 +721,14 @@ impl Network {
             // cache_update
             let fiber_is_full = fiber.is_full();
             fiber.delete(
-                demand.slot_heads[0],
-                demand.slot_width,
+                slot,
+                width,
                 core_index,
-                demand.index,
+                demand_index,
             );
 
             if fiber_is_full {
+                self.record_empty_fiber_ids_on_edge(&edge);
                 self.empty_fiber_ids_on_edges_cache
                     .get_mut(&edge)
                     .unwrap()
@@ -584,34 +740,37 @@ impl Network {
         }
     }
 
+    fn get_xc_id_on_node(&self, node: usize, xc_type: &XCType) -> Option<XCID> {
+        self.xc_ids_on_nodes
+            .get(node)
+            .and_then(|xc_ids| xc_ids[*xc_type as usize])
+    }
+
     pub fn get_xc_on_node(&self, node: usize, xc_type: &XCType) -> Option<&XC> {
-        if let Some((_, xc)) = self
-            .xcs
-            .iter()
-            .find(|(_, p)| p.node == node && p.xc_type == *xc_type)
-        {
-            Some(xc)
-        } else {
-            None
-        }
+        self.get_xc_id_on_node(node, xc_type)
+            .map(|xc_id| self.xcs.get(&xc_id).unwrap())
     }
 
+    /// ノード上のXCを返す (なければ作成する)
     pub fn get_xc_mut_on_node(&mut self, node: usize, xc_type: &XCType) -> &mut XC {
-        if self
-            .xcs
-            .iter()
-            .any(|(_, p)| p.node == node && p.xc_type == *xc_type)
-        {
-            return self
-                .xcs
-                .iter_mut()
-                .find(|(_, p)| p.node == node && p.xc_type == *xc_type)
-                .unwrap()
-                .1;
-        }
+        let xc_id = match self.get_xc_id_on_node(node, xc_type) {
+            Some(xc_id) => xc_id,
+            None => {
+                let xc = XC::new(self.generate_xc_id(), node, *xc_type);
+                let xc_id = xc.id;
+                self.record_xc(&xc_id);
+                self.record_xc_ids_on_nodes();
+                if self.xc_ids_on_nodes.len() <= node {
+                    self.xc_ids_on_nodes.resize(node + 1, [None; XC_TYPE_COUNT]);
+                }
+                self.xc_ids_on_nodes[node][*xc_type as usize] = Some(xc_id);
+                self.xcs.insert(xc_id, xc);
+                xc_id
+            }
+        };
 
-        let xc = XC::new(node, *xc_type);
-        self.xcs.entry(xc.id).or_insert(xc)
+        self.record_xc(&xc_id);
+        self.xcs.get_mut(&xc_id).unwrap()
     }
 
     pub fn get_xc_by_input_port_id(&self, input_device_id: &PortID) -> &XC {
@@ -638,8 +797,9 @@ impl Network {
         //     .unwrap();
         // xc
 
-        let xcid = self.portid_to_xcid.get(input_device_id).unwrap();
-        let xc = self.xcs.get_mut(xcid).unwrap();
+        let xcid = *self.portid_to_xcid.get(input_device_id).unwrap();
+        self.record_xc(&xcid);
+        let xc = self.xcs.get_mut(&xcid).unwrap();
 
         if !xc.has_input_device(input_device_id) {
             panic!();
@@ -672,8 +832,9 @@ impl Network {
         //     .unwrap();
         // xc
 
-        let xcid = self.portid_to_xcid.get(output_device_id).unwrap();
-        let xc = self.xcs.get_mut(xcid).unwrap();
+        let xcid = *self.portid_to_xcid.get(output_device_id).unwrap();
+        self.record_xc(&xcid);
+        let xc = self.xcs.get_mut(&xcid).unwrap();
 
         if !xc.has_output_device(output_device_id) {
             panic!();
@@ -703,6 +864,7 @@ impl Network {
             }
 
             // fiber_id_on_edges 関連
+            self.record_fiber_ids_on_edge(&fiber.edge);
             if let Some(fiber_id_on_edge) = self.fiber_ids_on_edges.get_mut(&fiber.edge) {
                 fiber_id_on_edge.push(fiber.fiber_id);
             } else {
@@ -715,6 +877,8 @@ impl Network {
         for fiber in fibers {
             let edge = fiber.edge;
             // cache_update
+            self.record_empty_fiber_ids_on_edge(&edge);
+            self.record_fiber(&fiber.fiber_id);
             self.empty_fiber_ids_on_edges_cache
                 .get_mut(&edge)
                 .unwrap()
@@ -733,6 +897,7 @@ impl Network {
         let fiber_id = fiber.fiber_id;
 
         // fiber_id_on_edges 関連
+        self.record_fiber_ids_on_edge(&fiber.edge);
         if let Some(fiber_id_on_edge) = self.fiber_ids_on_edges.get_mut(&fiber.edge) {
             fiber_id_on_edge.push(fiber.fiber_id);
         } else {
@@ -742,6 +907,8 @@ impl Network {
 
         let edge = fiber.edge;
         // cache_update
+        self.record_empty_fiber_ids_on_edge(&edge);
+        self.record_fiber(&fiber_id);
         self.empty_fiber_ids_on_edges_cache
             .get_mut(&edge)
             .unwrap()
@@ -881,7 +1048,7 @@ impl Network {
 
     pub fn get_fiber_sequence_as_edges(&self, first_fiber: &Fiber) -> Option<Vec<Edge>> {
         match self.get_fiber_sd_xc_type(first_fiber) {
-            [XCType::Wxc, XCType::Fxc] => {
+            [XCType::Wxc, XCType::Fxc] | [XCType::Added_Wxc, XCType::Fxc] => {
                 let fiber_seq = self.get_fiber_sequence(first_fiber);
                 Some(
                     fiber_seq
@@ -1144,6 +1311,7 @@ impl Network {
     }
 
     pub fn regist_port_id(&mut self, port_id: &PortID, xc_id: &XCID) {
+        self.record_port_id(port_id);
         self.portid_to_xcid.insert(*port_id, *xc_id);
     }
 
@@ -1155,6 +1323,7 @@ impl Network {
         if !UPDATE_LAYER_TOPOLOGIES {
             return;
         }
+        self.record_layer_topologies();
 
         self.layer_topologies.insert(
             XCType::Wxc,
@@ -1172,13 +1341,127 @@ impl Network {
     pub fn get_layer_topology(&self, xc_type: &XCType) -> &LayerTopology {
         self.layer_topologies.get(xc_type).unwrap()
     }
+
+    /// リンクを故障させる (SRLGの場合は複数のリンクを同時に指定する)
+    /// 両方向のリンクが故障する
+    pub fn fail_edges(&mut self, edges: &[Edge]) {
+        self.record_failures();
+        for edge in edges {
+            let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+            self.failed_edges.insert(Edge::new(src, dst));
+            self.failed_edges.insert(Edge::new(dst, src));
+        }
+    }
+
+    /// XCを故障させる
+    /// 故障したXCに接続されたファイバは使用できなくなり，XCで切り替えられたバイパスも断となる
+    pub fn fail_xc(&mut self, xc_id: XCID) {
+        assert!(self.xcs.contains_key(&xc_id));
+        self.record_failures();
+        self.failed_xc_ids.insert(xc_id);
+    }
+
+    /// ノードを故障させる (ノード上のすべてのXCが故障する)
+    pub fn fail_node(&mut self, node: usize) {
+        let xc_ids: Vec<XCID> = self.xc_ids_on_nodes
+            .get(node)
+            .map(|xc_ids| xc_ids.iter().flatten().copied().collect())
+            .unwrap_or_default();
+        self.record_failures();
+        self.failed_xc_ids.extend(xc_ids);
+    }
+
+    pub fn is_edge_failed(&self, edge: &Edge) -> bool {
+        self.failed_edges.contains(edge)
+    }
+
+    pub fn has_failed_xcs(&self) -> bool {
+        !self.failed_xc_ids.is_empty()
+    }
+
+    /// ファイバが故障したリンク上にあるか，両端のいずれかが故障したXCに接続されているか
+    pub fn is_fiber_failed(&self, fiber_id: &FiberID) -> bool {
+        let fiber = self.get_fiber_by_id(fiber_id);
+        if self.is_edge_failed(&fiber.edge) {
+            return true;
+        }
+
+        // MCFのポートはすべて同じXCに属するため，先頭のポートのみ確認する
+        fiber.src_port_ids
+            .first()
+            .into_iter()
+            .chain(fiber.dst_port_ids.first())
+            .any(|port_id| {
+                self.portid_to_xcid
+                    .get(port_id)
+                    .is_some_and(|xc_id| self.failed_xc_ids.contains(xc_id))
+            })
+    }
+
+    /// ファイバルートが故障箇所を通過するか
+    pub fn is_fiber_route_failed(&self, fiber_ids: &[FiberID]) -> bool {
+        fiber_ids
+            .iter()
+            .any(|fiber_id| self.is_fiber_failed(fiber_id))
+    }
+
+    /// 現用パスが故障の影響を受けるDemandのインデックス
+    /// バイパスを構成するファイバも含め，経路上のすべてのファイバを確認する
+    pub fn get_affected_demand_indices(&self, demand_list: &[Demand]) -> Vec<usize> {
+        demand_list
+            .iter()
+            .enumerate()
+            .filter(|(_, demand)| !demand.fiber_ids.is_empty() && self.is_fiber_route_failed(&demand.fiber_ids))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// 現用パスのみをNetworkから削除する (予備パスは維持する)
+    pub fn remove_working_path(&mut self, demand: &Demand) {
+        self.remove_fiber_route(demand.slot_heads[0], demand.slot_width, &demand.fiber_ids, &demand.core_indices, demand.index);
+        self.record_working_route(demand.index);
+        self.working_routes.remove(&demand.index);
+    }
+
+    /// 指定したファイバ・コア・スロットを使用している現用パスのDemandのindex
+    /// 予備パスの予約は信号を伝送しないため含まない
+    pub fn find_working_demand_index(&self, fiber_id: &FiberID, core_index: &CoreIndex, slot: usize) -> Option<usize> {
+        self.get_fiber_by_id(fiber_id)
+            .assigned_demand_ids
+            .iter()
+            .copied()
+            .find(|demand_index| {
+                self.working_routes
+                    .get(demand_index)
+                    .is_some_and(|route| route.uses(fiber_id, core_index, slot))
+            })
+    }
+
+    pub fn get_working_route(&self, demand_index: usize) -> Option<&WorkingRoute> {
+        self.working_routes.get(&demand_index)
+    }
+
+    pub fn get_crosstalk_model(&self) -> &CrosstalkModel {
+        &self.crosstalk_model
+    }
+
+    /// 指定したファイバ・コア・スロットでパスを収容したときに，クロストークが許容値以下か
+    pub fn is_crosstalk_admissible(
+        &self,
+        fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        slot: usize,
+    ) -> bool {
+        self.crosstalk_model
+            .is_admissible(self, fiber_ids, core_indices, slot)
+    }
 }
 
 mod fiber;
 pub use fiber::{CoreIndex, Fiber, FiberID, FiberType};
 
 use self::xc::xc_type_to_quality_distance;
-pub use self::xc::{XCType, XC};
+pub use self::xc::{XCType, XC, XCID, XC_TYPE_COUNT};
 
 mod xc;
 
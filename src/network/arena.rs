@@ -0,0 +1,99 @@
+use std::marker::PhantomData;
+
+use serde_derive::{ Deserialize, Serialize };
+
+/// `Arena`のキーとなるID (`IdCounters`が0から順に発行する)
+pub trait ArenaKey: Copy {
+    fn index(&self) -> usize;
+    fn from_index(index: usize) -> Self;
+}
+
+/// IDを添字とする密な配列による格納領域
+///
+/// IDは0から順に発行されるため，ハッシュマップより検索・複製が速い．
+/// 削除した要素の位置は空き (`None`) のまま残し，IDは再利用しない．
+/// 走査はIDの順 (発行順) に行う
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Arena<K, V> {
+    slots: Vec<Option<V>>,
+    #[serde(skip)]
+    _key: PhantomData<K>,
+}
+
+impl<K, V> Default for Arena<K, V> {
+    fn default() -> Self {
+        Self {
+            slots: vec![],
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K: ArenaKey, V> Arena<K, V> {
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.slots.get(key.index()).and_then(|slot| slot.as_ref())
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.slots.get_mut(key.index()).and_then(|slot| slot.as_mut())
+    }
+
+    /// 要素を格納し，同じIDの要素があれば返す
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index = key.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        self.slots[index].replace(value)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.slots.get_mut(key.index()).and_then(|slot| slot.take())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.slots.iter().flatten()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.as_ref().map(|value| (K::from_index(index), value)))
+    }
+}
+
+#[test]
+fn arena_test() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Key(usize);
+    impl ArenaKey for Key {
+        fn index(&self) -> usize {
+            self.0
+        }
+        fn from_index(index: usize) -> Self {
+            Key(index)
+        }
+    }
+
+    let mut arena: Arena<Key, &str> = Arena::default();
+    assert_eq!(arena.insert(Key(2), "c"), None);
+    assert_eq!(arena.insert(Key(0), "a"), None);
+    assert_eq!(arena.get(&Key(1)), None);
+    assert_eq!(arena.get(&Key(5)), None);
+
+    // 上書きすると元の要素を返す
+    assert_eq!(arena.insert(Key(2), "C"), Some("c"));
+
+    // 走査はIDの順
+    assert_eq!(arena.iter().collect::<Vec<_>>(), vec![(Key(0), &"a"), (Key(2), &"C")]);
+
+    assert_eq!(arena.remove(&Key(0)), Some("a"));
+    assert_eq!(arena.remove(&Key(0)), None);
+    assert!(!arena.contains_key(&Key(0)));
+    assert_eq!(arena.values().collect::<Vec<_>>(), vec![&"C"]);
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use fxhash::FxHashMap;
+use strum::IntoEnumIterator;
+
+use crate::{config::Config, demand::Demand, np_core::parameters::WAVEBAND_COUNT};
+
+use super::{FiberType, Network, XCType};
+
+/// 設備コストの内訳
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostItems {
+    /// ファイバ (長さ比例)
+    pub fiber: f64,
+    /// XCのポート
+    pub port: f64,
+    /// 送受信器
+    pub transceiver: f64,
+}
+
+impl CostItems {
+    pub fn total(&self) -> f64 {
+        self.fiber + self.port + self.transceiver
+    }
+
+    fn add(&mut self, other: &CostItems) {
+        self.fiber += other.fiber;
+        self.port += other.port;
+        self.transceiver += other.transceiver;
+    }
+}
+
+/// ノード・レイヤ (XCの種類) ごとの設備コスト
+///
+/// ファイバのコストは両端のノード・レイヤに半分ずつ，送受信器は始点・終点ノードのWXCに計上する
+#[derive(Debug, Clone, Default)]
+pub struct CostBreakdown {
+    items: FxHashMap<(usize, XCType), CostItems>,
+}
+
+impl CostBreakdown {
+    fn entry(&mut self, node: usize, xc_type: XCType) -> &mut CostItems {
+        self.items.entry((node, xc_type)).or_default()
+    }
+
+    /// NW全体の設備コスト
+    pub fn total(&self) -> CostItems {
+        let mut total = CostItems::default();
+        for items in self.items.values() {
+            total.add(items);
+        }
+        total
+    }
+
+    /// ノードごとの設備コスト (ノード順)
+    pub fn by_node(&self) -> BTreeMap<usize, CostItems> {
+        let mut by_node: BTreeMap<usize, CostItems> = BTreeMap::new();
+        for (&(node, _), items) in &self.items {
+            by_node.entry(node).or_default().add(items);
+        }
+        by_node
+    }
+
+    /// レイヤごとの設備コスト (`XCType`の定義順，コストのないレイヤは含まない)
+    pub fn by_layer(&self) -> Vec<(XCType, CostItems)> {
+        XCType::iter()
+            .filter_map(|xc_type| {
+                let mut layer = CostItems::default();
+                let mut exists = false;
+                for (_, items) in self.items.iter().filter(|((_, t), _)| *t == xc_type) {
+                    layer.add(items);
+                    exists = true;
+                }
+                exists.then_some((xc_type, layer))
+            })
+            .collect()
+    }
+}
+
+/// XCの1ポートあたりのコスト
+/// WBXCは1ポートが波長帯数分の波長帯ポートを持つとする
+fn port_cost(config: &Config, xc_type: XCType) -> f64 {
+    match xc_type {
+        XCType::Wxc | XCType::Added_Wxc => config.cost.wxc_port,
+        XCType::Fxc | XCType::Sxc => config.cost.fxc_port,
+        XCType::Wbxc => config.cost.wbxc_waveband_port * WAVEBAND_COUNT as f64,
+    }
+}
+
+impl Network {
+    /// 設備コスト (CAPEX) を計算する
+    /// 送受信器はパス需要の両端に1台ずつ置くとする
+    pub fn calc_cost_breakdown(&self, config: &Config, demand_list: &[Demand]) -> CostBreakdown {
+        let mut breakdown = CostBreakdown::default();
+
+        for fiber in self.fibers.values() {
+            let length = if fiber.distance > 0 {
+                fiber.distance as f64
+            } else {
+                config.cost.default_fiber_length
+            };
+            let per_km = match fiber.fiber_type {
+                FiberType::Scf => config.cost.scf_per_km,
+                FiberType::Mcf => config.cost.mcf_per_km,
+            };
+            let half = length * per_km / 2.0;
+            let [src_xc_type, dst_xc_type] = fiber.sd_xc_type;
+            breakdown.entry(fiber.edge.src.into(), src_xc_type).fiber += half;
+            breakdown.entry(fiber.edge.dst.into(), dst_xc_type).fiber += half;
+        }
+
+        for xc in self.xcs.values().filter(|xc| xc.get_size() > 0) {
+            breakdown.entry(xc.node, xc.xc_type).port += xc.get_size() as f64 * port_cost(config, xc.xc_type);
+        }
+
+        for demand in demand_list {
+            breakdown.entry(demand.sd.src.into(), XCType::Wxc).transceiver += config.cost.transceiver;
+            breakdown.entry(demand.sd.dst.into(), XCType::Wxc).transceiver += config.cost.transceiver;
+        }
+
+        breakdown
+    }
+
+    /// 設備コストの合計
+    pub fn calc_total_cost(&self, config: &Config, demand_list: &[Demand]) -> f64 {
+        self.calc_cost_breakdown(config, demand_list).total().total()
+    }
+}
+
+#[test]
+fn cost_breakdown_test() {
+    let mut breakdown = CostBreakdown::default();
+    breakdown.entry(0, XCType::Wxc).port += 2.0;
+    breakdown.entry(0, XCType::Fxc).fiber += 1.0;
+    breakdown.entry(1, XCType::Wxc).transceiver += 4.0;
+
+    assert_eq!(breakdown.total().total(), 7.0);
+
+    let by_node = breakdown.by_node();
+    assert_eq!(by_node[&0].total(), 3.0);
+    assert_eq!(by_node[&1].total(), 4.0);
+
+    let by_layer = breakdown.by_layer();
+    assert_eq!(by_layer.len(), 2);
+    assert_eq!(by_layer[0].0, XCType::Wxc);
+    assert_eq!(by_layer[0].1, CostItems { fiber: 0.0, port: 2.0, transceiver: 4.0 });
+    assert_eq!(by_layer[1].1.fiber, 1.0);
+}
@@ -0,0 +1,302 @@
+use crate::config::Config;
+use fxhash::FxHashMap;
+use serde_derive::{ Deserialize, Serialize };
+
+use super::{CoreIndex, Fiber, FiberID, FiberType, Network};
+
+/// MCFのコア配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoreLayout {
+    /// 一列に並んだコア配置
+    Linear,
+    /// 格子状のコア配置 (4コアなら2x2)
+    Square,
+    /// 中心コア + 周辺リングのコア配置 (7コアなら六方細密)
+    Hexagonal,
+}
+
+impl CoreLayout {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_uppercase().as_str() {
+            "LINEAR" => CoreLayout::Linear,
+            "SQUARE" => CoreLayout::Square,
+            "HEXAGONAL" | "HEX" => CoreLayout::Hexagonal,
+            _ => panic!("Invalid `core_layout`: {}", name),
+        }
+    }
+
+    /// `core_num`本のコアを持つファイバにおいて，`core_index`に隣接するコアを返す
+    pub fn neighbors(&self, core_index: &CoreIndex, core_num: usize) -> Vec<CoreIndex> {
+        let index = core_index.index();
+        let mut output = vec![];
+
+        match self {
+            CoreLayout::Linear => {
+                if index > 0 {
+                    output.push(index - 1);
+                }
+                if index + 1 < core_num {
+                    output.push(index + 1);
+                }
+            }
+            CoreLayout::Square => {
+                let width = (core_num as f64).sqrt().ceil() as usize;
+                let (row, col) = (index / width, index % width);
+
+                if row > 0 {
+                    output.push(index - width);
+                }
+                if col > 0 {
+                    output.push(index - 1);
+                }
+                if col + 1 < width && index + 1 < core_num {
+                    output.push(index + 1);
+                }
+                if index + width < core_num {
+                    output.push(index + width);
+                }
+            }
+            CoreLayout::Hexagonal => {
+                // コア0を中心とし，残りのコアが中心の周りにリング状に並ぶ
+                let ring_num = core_num.saturating_sub(1);
+                if index == 0 {
+                    output.extend(1..core_num);
+                } else {
+                    output.push(0);
+                    if ring_num > 1 {
+                        let ring_index = index - 1;
+                        let prev = (ring_index + ring_num - 1) % ring_num + 1;
+                        let next = (ring_index + 1) % ring_num + 1;
+                        output.push(prev);
+                        if next != prev {
+                            output.push(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        output.into_iter().map(CoreIndex::new).collect()
+    }
+}
+
+/// 収容中の現用パスの経路
+/// 新規パスが隣接コアの既存パスへ与えるクロストークを求めるために保持する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkingRoute {
+    pub fiber_ids: Vec<FiberID>,
+    pub core_indices: Vec<CoreIndex>,
+    pub slot_head: usize,
+    pub slot_width: usize,
+}
+
+impl WorkingRoute {
+    /// 指定したファイバ・コア・スロットを使用しているか
+    pub fn uses(&self, fiber_id: &FiberID, core_index: &CoreIndex, slot: usize) -> bool {
+        (self.slot_head..self.slot_head + self.slot_width).contains(&slot)
+            && self.fiber_ids
+                .iter()
+                .zip(self.core_indices.iter())
+                .any(|(f, c)| f == fiber_id && c == core_index)
+    }
+}
+
+/// 隣接コアの占有状況とファイバ長に基づくコア間クロストークの推定モデル
+///
+/// 単位長あたりの電力結合係数 h = 2κ²R / (βΛ) を用い，
+/// あるスロットのクロストークを (同一スロットを使用中の隣接コア数) × h × L として求める．
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrosstalkModel {
+    layout: CoreLayout,
+    /// 単位長あたりの電力結合係数 h [1/m]
+    coupling_per_meter: f64,
+    /// ファイバ長が未設定の場合のファイバ長 [m]
+    default_fiber_length: f64,
+    threshold_db: f64,
+    admission_check: bool,
+}
+
+impl CrosstalkModel {
+    pub fn new(config: &Config) -> Self {
+        let xt_config = &config.crosstalk;
+        let kappa = xt_config.coupling_coefficient;
+
+        Self {
+            layout: CoreLayout::from_name(&xt_config.core_layout),
+            coupling_per_meter: 2.0 * kappa * kappa * xt_config.bend_radius
+                / (xt_config.propagation_constant * xt_config.core_pitch),
+            default_fiber_length: xt_config.default_fiber_length * 1000.0,
+            threshold_db: xt_config.threshold_db,
+            admission_check: xt_config.admission_check,
+        }
+    }
+
+    /// ファイバ長 [m]，`Fiber::distance`が0の場合は設定値を用いる
+    fn fiber_length(&self, fiber: &Fiber) -> f64 {
+        if fiber.distance > 0 {
+            fiber.distance as f64 * 1000.0
+        } else {
+            self.default_fiber_length
+        }
+    }
+
+    /// 指定したコア・スロットが受けるクロストーク (真値)
+    /// SCFの場合は常に0
+    pub fn calc_slot_crosstalk(&self, fiber: &Fiber, core_index: &CoreIndex, slot: usize) -> f64 {
+        if fiber.fiber_type != FiberType::Mcf {
+            return 0.0;
+        }
+
+        let occupied_neighbor_count = self
+            .layout
+            .neighbors(core_index, fiber.get_core_num())
+            .iter()
+            .filter(|neighbor| fiber.state_matrixes[neighbor.index()][slot])
+            .count();
+
+        occupied_neighbor_count as f64 * self.coupling_per_meter * self.fiber_length(fiber)
+    }
+
+    /// ファイバルート全体で累積するクロストーク [dB]
+    pub fn calc_route_crosstalk_db(
+        &self,
+        network: &Network,
+        fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        slot: usize,
+    ) -> f64 {
+        let crosstalk: f64 = fiber_ids
+            .iter()
+            .zip(core_indices.iter())
+            .map(|(fiber_id, core_index)| {
+                self.calc_slot_crosstalk(network.get_fiber_by_id(fiber_id), core_index, slot)
+            })
+            .sum();
+
+        to_db(crosstalk)
+    }
+
+    /// 新規パスを収容したときのクロストークが許容値以下か
+    /// 新規パス自身に加え，新規パスから隣接コアのクロストークを受ける既存の現用パスも判定する．
+    /// 受付判定が無効の場合は常に`true`
+    pub fn is_admissible(
+        &self,
+        network: &Network,
+        fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        slot: usize,
+    ) -> bool {
+        if !self.admission_check {
+            return true;
+        }
+
+        if self.calc_route_crosstalk_db(network, fiber_ids, core_indices, slot) > self.threshold_db {
+            return false;
+        }
+
+        self.calc_added_crosstalk(network, fiber_ids, core_indices, slot)
+            .into_iter()
+            .all(|(demand_index, added_crosstalk)| {
+                let route = network.get_working_route(demand_index).unwrap();
+                let crosstalk: f64 = route.fiber_ids
+                    .iter()
+                    .zip(route.core_indices.iter())
+                    .map(|(fiber_id, core_index)| {
+                        self.calc_slot_crosstalk(network.get_fiber_by_id(fiber_id), core_index, slot)
+                    })
+                    .sum();
+                to_db(crosstalk + added_crosstalk) <= self.threshold_db
+            })
+    }
+
+    /// 新規パスを収容したときに，隣接コアの同一スロットを使用する既存の現用パスが新たに受けるクロストーク
+    /// (Demandのindex -> 増加するクロストーク (真値))
+    fn calc_added_crosstalk(
+        &self,
+        network: &Network,
+        fiber_ids: &[FiberID],
+        core_indices: &[CoreIndex],
+        slot: usize,
+    ) -> FxHashMap<usize, f64> {
+        let mut added_crosstalk: FxHashMap<usize, f64> = FxHashMap::default();
+
+        for (fiber_id, core_index) in fiber_ids.iter().zip(core_indices.iter()) {
+            let fiber = network.get_fiber_by_id(fiber_id);
+            if fiber.fiber_type != FiberType::Mcf {
+                continue;
+            }
+
+            for neighbor in self.layout.neighbors(core_index, fiber.get_core_num()) {
+                if !fiber.state_matrixes[neighbor.index()][slot] {
+                    continue;
+                }
+                if let Some(demand_index) = network.find_working_demand_index(fiber_id, &neighbor, slot) {
+                    *added_crosstalk.entry(demand_index).or_default() +=
+                        self.coupling_per_meter * self.fiber_length(fiber);
+                }
+            }
+        }
+
+        added_crosstalk
+    }
+
+    /// ファイバ内の使用中スロットが受けるクロストークの最悪値 [dB]
+    pub fn calc_fiber_worst_crosstalk_db(&self, fiber: &Fiber) -> f64 {
+        let mut worst = 0.0;
+
+        for core_index in (0..fiber.get_core_num()).map(CoreIndex::new) {
+            for (slot, occupied) in fiber.state_matrixes[core_index.index()].iter().enumerate() {
+                if *occupied {
+                    let crosstalk = self.calc_slot_crosstalk(fiber, &core_index, slot);
+                    if worst < crosstalk {
+                        worst = crosstalk;
+                    }
+                }
+            }
+        }
+
+        to_db(worst)
+    }
+}
+
+/// 真値からdBへ変換する (0以下は -inf)
+pub fn to_db(value: f64) -> f64 {
+    if value <= 0.0 {
+        f64::NEG_INFINITY
+    } else {
+        10.0 * value.log10()
+    }
+}
+
+#[test]
+fn admission_with_neighbor_paths_test() {
+    use crate::{config::test_config, demand::Demand, network::XCType, np_core::parameters::CORE_FACTOR, topology::Topology, SD};
+
+    let mut config = test_config();
+    config.crosstalk.core_layout = "LINEAR".to_string();
+    config.crosstalk.admission_check = true;
+    // 隣接コア1本分 (約 -50.5 dB) は許容し，2本分 (約 -47.5 dB) は許容しない
+    config.crosstalk.threshold_db = -49.0;
+    let topology = Topology::new(&config);
+    let mut network = Network::new(&config, &topology, &[XCType::Wxc, XCType::Fxc]);
+
+    let edge = network.edges[0];
+    let src_port_ids = (0..CORE_FACTOR).map(|_| network.generate_port_id()).collect();
+    let dst_port_ids = (0..CORE_FACTOR).map(|_| network.generate_port_id()).collect();
+    let fiber = Fiber::new_mcf(network.generate_fiber_id(), &edge, src_port_ids, dst_port_ids, [XCType::Wxc, XCType::Wxc]);
+    let fiber_id = network.regist_fiber(fiber).fiber_id;
+    let sd = SD::new(edge.src.into(), edge.dst.into());
+
+    // コア1の既存パスは，コア0の新規パスからのクロストークを許容できる
+    network.assign_path(vec![0], &[fiber_id], &[CoreIndex::new(1)], None, &Demand::new(sd, 0, 0, 1));
+    assert!(network.is_crosstalk_admissible(&[fiber_id], &[CoreIndex::new(0)], 0));
+    network.assign_path(vec![0], &[fiber_id], &[CoreIndex::new(0)], None, &Demand::new(sd, 1, 0, 1));
+
+    // コア2の新規パス自身は隣接コア1本分で許容値以下だが，両側を挟まれるコア1の既存パスが許容値を超える
+    let model = network.get_crosstalk_model();
+    assert!(model.calc_route_crosstalk_db(&network, &[fiber_id], &[CoreIndex::new(2)], 0) <= -49.0);
+    assert!(!network.is_crosstalk_admissible(&[fiber_id], &[CoreIndex::new(2)], 0));
+
+    // 異なるスロットには影響しない
+    assert!(network.is_crosstalk_admissible(&[fiber_id], &[CoreIndex::new(2)], 1));
+}
@@ -1,20 +1,23 @@
 use core::fmt;
+use serde_derive::{ Deserialize, Serialize };
 
-use fxhash::FxHashSet;
-use uuid::Uuid;
+use fxhash::{FxHashMap, FxHashSet};
 
-use crate::{ np_core::{parameters::CORE_FACTOR, StateMatrix}, utils::generate_uuid, Edge, SLOT };
+use crate::{ np_core::{parameters::CORE_FACTOR, StateMatrix}, Edge, SLOT };
 
-use super::{xc::PortID, XCType};
+use super::{arena::ArenaKey, protection::{is_risk_disjoint, BackupReservation, RiskGroup}, xc::PortID, XCType};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct FiberID (Uuid);
-// impl FiberID {
-//     pub(crate) fn nil() -> Self {
-//         FiberID(Uuid::nil())
-//     }
-// }
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FiberID (usize);
+impl ArenaKey for FiberID {
+    fn index(&self) -> usize {
+        self.0
+    }
+    fn from_index(index: usize) -> Self {
+        FiberID(index)
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct CoreIndex (usize);
 impl CoreIndex {
     pub fn new(value: usize) -> Self {
@@ -39,13 +42,13 @@ impl From<usize> for CoreIndex {
 }
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FiberType {
     Scf = 0,
     Mcf = 1
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fiber {
     pub fiber_id: FiberID,
     pub edge: Edge,
@@ -57,7 +60,10 @@ pub struct Fiber {
     pub dst_port_ids: Vec<PortID>,
     pub sd_xc_type: [XCType; 2],
     pub distance: usize,
-    pub fiber_type: FiberType
+    pub fiber_type: FiberType,
+    /// 共有予備パスの予約 (コア, スロット) -> 共有している予約
+    /// 予約されたスロットは`state_matrixes`上でも使用中となる
+    pub backup_reservations: FxHashMap<(CoreIndex, usize), Vec<BackupReservation>>,
 }
 
 impl fmt::Display for FiberID {
@@ -70,35 +76,37 @@ impl fmt::Display for FiberID {
 impl Fiber {
     /// # ONLY MAKE FIBER STURCT!!!
     /// # THIS FUNCTION DOES NOT MANAGE REGISTRATION TO NETWORK!!
-    pub fn new_scf(edge: &Edge, src_port_id: PortID, dst_port_id: PortID, sd_xc_type: [XCType; 2]) -> Fiber {
+    pub fn new_scf(fiber_id: FiberID, edge: &Edge, src_port_id: PortID, dst_port_id: PortID, sd_xc_type: [XCType; 2]) -> Fiber {
         Fiber {
             state_matrixes: vec![StateMatrix::new()],
             edge: *edge,
             assigned_demand_ids: FxHashSet::default(),
             occupancy: 0,
             residual: SLOT,
-            fiber_id: FiberID(generate_uuid()),
+            fiber_id,
             src_port_ids: vec![src_port_id],
             dst_port_ids: vec![dst_port_id],
             sd_xc_type,
             distance: 0,
             fiber_type: FiberType::Scf,
+            backup_reservations: FxHashMap::default(),
         }
     }
 
-    pub fn new_mcf(edge: &Edge, src_port_ids: Vec<PortID>, dst_port_ids: Vec<PortID>, sd_xc_type: [XCType; 2]) -> Fiber {
+    pub fn new_mcf(fiber_id: FiberID, edge: &Edge, src_port_ids: Vec<PortID>, dst_port_ids: Vec<PortID>, sd_xc_type: [XCType; 2]) -> Fiber {
         Fiber {
             state_matrixes: vec![StateMatrix::new(); CORE_FACTOR],
             edge: *edge,
             assigned_demand_ids: FxHashSet::default(),
             occupancy: 0,
             residual: SLOT*CORE_FACTOR,
-            fiber_id: FiberID(generate_uuid()),
+            fiber_id,
             src_port_ids,
             dst_port_ids,
             sd_xc_type,
             distance: 0,
             fiber_type: FiberType::Mcf,
+            backup_reservations: FxHashMap::default(),
         }
     }
 
@@ -151,6 +159,75 @@ impl Fiber {
         self.residual += width;
     }
 
+    /// 共有予備パスとしてスロットを予約する
+    /// 予約済みのスロットは，既存の予約と`risk_groups`が独立である場合のみ共有できる
+    pub fn reserve_backup(&mut self, slot: usize, width: usize, core_index: &CoreIndex, demand_id: usize, risk_groups: &[RiskGroup]) {
+        if self.assigned_demand_ids.contains(&demand_id) {
+            eprintln!("{}", demand_id);
+            panic!("This demand path is already assigend to the fiber");
+        }
+
+        for s in slot..slot+width {
+            let reservation = BackupReservation { demand_id, risk_groups: risk_groups.to_vec() };
+            match self.backup_reservations.get_mut(&(*core_index, s)) {
+                Some(reservations) => {
+                    if !reservations.iter().all(|r| is_risk_disjoint(&r.risk_groups, risk_groups)) {
+                        panic!("Slot {} is reserved by a backup path sharing the risk group.", s);
+                    }
+                    reservations.push(reservation);
+                }
+                None => {
+                    if self.state_matrixes[core_index.index()][s] {
+                        panic!("Slot {} is occupied. Use another slots.", s);
+                    }
+                    self.state_matrixes[core_index.index()][s] = true;
+                    self.occupancy += 1;
+                    self.residual -= 1;
+                    self.backup_reservations.insert((*core_index, s), vec![reservation]);
+                }
+            }
+        }
+        self.assigned_demand_ids.insert(demand_id);
+    }
+
+    /// 共有予備パスの予約を解除する
+    /// 共有している予約がなくなったスロットは解放される
+    pub fn release_backup(&mut self, slot: usize, width: usize, core_index: &CoreIndex, demand_id: usize) {
+        if !self.assigned_demand_ids.contains(&demand_id) {
+            eprintln!("{}", demand_id);
+            panic!("This demand path is not assigend to this fiber");
+        }
+
+        for s in slot..slot+width {
+            let Some(reservations) = self.backup_reservations.get_mut(&(*core_index, s)) else {
+                panic!("Slot {} is not reserved. Something went wrong.", s);
+            };
+            reservations.retain(|r| r.demand_id != demand_id);
+
+            if reservations.is_empty() {
+                self.backup_reservations.remove(&(*core_index, s));
+                self.state_matrixes[core_index.index()][s] = false;
+                self.occupancy -= 1;
+                self.residual += 1;
+            }
+        }
+        self.assigned_demand_ids.remove(&demand_id);
+    }
+
+    /// `risk_groups`を保護する予備パスから見たスロット状態
+    /// 共有可能な予約済みスロットを空きとみなす
+    pub fn get_shareable_state_matrix(&self, core_index: &CoreIndex, risk_groups: &[RiskGroup]) -> StateMatrix {
+        let mut state_matrix = self.state_matrixes[core_index.index()];
+        for ((reserved_core_index, slot), reservations) in &self.backup_reservations {
+            if reserved_core_index == core_index
+                && reservations.iter().all(|r| is_risk_disjoint(&r.risk_groups, risk_groups))
+            {
+                state_matrix[*slot] = false;
+            }
+        }
+        state_matrix
+    }
+
     pub fn is_full(&self) -> bool {
         for core_index_as_usize in 0..self.get_core_num() {
             if self.state_matrixes[core_index_as_usize].has_empty_contiguous_slots(1) {
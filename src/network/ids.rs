@@ -0,0 +1,47 @@
+use serde_derive::{ Deserialize, Serialize };
+
+use super::{arena::ArenaKey, FiberID, Network, PortID, XCID};
+
+/// ファイバ・XC・ポートのIDの発行元
+///
+/// IDはNWごとに0から順に発行するため，同じ設定・シードの設計では実行ごとに同じIDとなり，
+/// IDをキーとするハッシュマップの走査順も変わらない
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub(super) struct IdCounters {
+    fiber: usize,
+    xc: usize,
+    port: usize,
+}
+
+impl IdCounters {
+    pub(super) fn next_fiber_id(&mut self) -> FiberID {
+        self.fiber += 1;
+        FiberID::from_index(self.fiber - 1)
+    }
+
+    pub(super) fn next_xc_id(&mut self) -> XCID {
+        self.xc += 1;
+        XCID::from_index(self.xc - 1)
+    }
+
+    pub(super) fn next_port_id(&mut self) -> PortID {
+        self.port += 1;
+        PortID::from_index(self.port - 1)
+    }
+}
+
+impl Network {
+    /// 新しいファイバのIDを発行する
+    pub fn generate_fiber_id(&mut self) -> FiberID {
+        self.id_counters.next_fiber_id()
+    }
+
+    /// 新しいポートのIDを発行する
+    pub fn generate_port_id(&mut self) -> PortID {
+        self.id_counters.next_port_id()
+    }
+
+    pub(super) fn generate_xc_id(&mut self) -> XCID {
+        self.id_counters.next_xc_id()
+    }
+}
@@ -0,0 +1,215 @@
+use std::hash::Hash;
+
+use fxhash::{FxHashMap, FxHashSet};
+use rand_chacha::ChaCha8Rng;
+
+use crate::Edge;
+
+use super::{arena::{Arena, ArenaKey}, crosstalk::WorkingRoute, ids::IdCounters, layer_top::LayerTopology, Fiber, FiberID, Network, PortID, XCType, XC, XCID, XC_TYPE_COUNT};
+
+/// トランザクション中の変更の記録
+///
+/// 変更された要素ごとに，最初に変更される直前の値を保持する (`None`はトランザクション中に追加された要素)．
+/// XCの接続 (`connect_io`など) はXC単位で記録する．
+#[derive(Debug, Clone)]
+pub(super) struct Journal {
+    fibers: FxHashMap<FiberID, Option<Fiber>>,
+    fiber_ids_on_edges: FxHashMap<Edge, Option<Vec<FiberID>>>,
+    empty_fiber_ids_on_edges_cache: FxHashMap<Edge, Option<Vec<FiberID>>>,
+    edge_costs: FxHashMap<Edge, Option<f64>>,
+    xcs: FxHashMap<XCID, Option<XC>>,
+    portid_to_xcid: FxHashMap<PortID, Option<XCID>>,
+    working_routes: FxHashMap<usize, Option<WorkingRoute>>,
+    rng: ChaCha8Rng,
+    id_counters: IdCounters,
+    layer_topologies: Option<FxHashMap<XCType, LayerTopology>>,
+    xc_ids_on_nodes: Option<Vec<[Option<XCID>; XC_TYPE_COUNT]>>,
+    failed_edges: Option<FxHashSet<Edge>>,
+    failed_xc_ids: Option<FxHashSet<XCID>>,
+}
+
+/// 変更前の値に戻す
+fn restore<K: Hash + Eq, V>(map: &mut FxHashMap<K, V>, prior_values: FxHashMap<K, Option<V>>) {
+    for (key, prior_value) in prior_values {
+        match prior_value {
+            Some(value) => {
+                map.insert(key, value);
+            }
+            None => {
+                map.remove(&key);
+            }
+        }
+    }
+}
+
+/// 変更前の値に戻す (`Arena`版)
+fn restore_arena<K: ArenaKey, V>(arena: &mut Arena<K, V>, prior_values: FxHashMap<K, Option<V>>) {
+    for (key, prior_value) in prior_values {
+        match prior_value {
+            Some(value) => {
+                arena.insert(key, value);
+            }
+            None => {
+                arena.remove(&key);
+            }
+        }
+    }
+}
+
+impl Network {
+    /// トランザクションを開始する
+    /// 以降の変更は`rollback`で取り消せる．入れ子にはできない
+    pub fn begin(&mut self) {
+        assert!(!self.in_transaction(), "トランザクションは既に開始されています");
+        self.journal = Some(Box::new(Journal {
+            fibers: FxHashMap::default(),
+            fiber_ids_on_edges: FxHashMap::default(),
+            empty_fiber_ids_on_edges_cache: FxHashMap::default(),
+            edge_costs: FxHashMap::default(),
+            xcs: FxHashMap::default(),
+            portid_to_xcid: FxHashMap::default(),
+            working_routes: FxHashMap::default(),
+            rng: self.rng.clone(),
+            id_counters: self.id_counters,
+            layer_topologies: None,
+            xc_ids_on_nodes: None,
+            failed_edges: None,
+            failed_xc_ids: None,
+        }));
+    }
+
+    /// トランザクション中の変更を確定する
+    pub fn commit(&mut self) {
+        assert!(self.in_transaction(), "トランザクションが開始されていません");
+        self.journal = None;
+    }
+
+    /// トランザクション中の変更をすべて取り消す
+    pub fn rollback(&mut self) {
+        let journal = self.journal.take().expect("トランザクションが開始されていません");
+
+        restore_arena(&mut self.fibers, journal.fibers);
+        restore(&mut self.fiber_ids_on_edges, journal.fiber_ids_on_edges);
+        restore(&mut self.empty_fiber_ids_on_edges_cache, journal.empty_fiber_ids_on_edges_cache);
+        restore(&mut self.edge_costs, journal.edge_costs);
+        restore_arena(&mut self.xcs, journal.xcs);
+        restore_arena(&mut self.portid_to_xcid, journal.portid_to_xcid);
+        restore(&mut self.working_routes, journal.working_routes);
+        self.rng = journal.rng;
+        self.id_counters = journal.id_counters;
+        if let Some(layer_topologies) = journal.layer_topologies {
+            self.layer_topologies = layer_topologies;
+        }
+        if let Some(xc_ids_on_nodes) = journal.xc_ids_on_nodes {
+            self.xc_ids_on_nodes = xc_ids_on_nodes;
+        }
+        if let Some(failed_edges) = journal.failed_edges {
+            self.failed_edges = failed_edges;
+        }
+        if let Some(failed_xc_ids) = journal.failed_xc_ids {
+            self.failed_xc_ids = failed_xc_ids;
+        }
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    pub(super) fn record_fiber(&mut self, fiber_id: &FiberID) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.fibers.entry(*fiber_id).or_insert_with(|| self.fibers.get(fiber_id).cloned());
+        }
+    }
+
+    pub(super) fn record_fiber_ids_on_edge(&mut self, edge: &Edge) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.fiber_ids_on_edges.entry(*edge).or_insert_with(|| self.fiber_ids_on_edges.get(edge).cloned());
+        }
+    }
+
+    pub(super) fn record_empty_fiber_ids_on_edge(&mut self, edge: &Edge) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.empty_fiber_ids_on_edges_cache.entry(*edge).or_insert_with(|| self.empty_fiber_ids_on_edges_cache.get(edge).cloned());
+        }
+    }
+
+    pub(super) fn record_edge_cost(&mut self, edge: &Edge) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.edge_costs.entry(*edge).or_insert_with(|| self.edge_costs.get(edge).copied());
+        }
+    }
+
+    pub(super) fn record_xc(&mut self, xc_id: &XCID) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.xcs.entry(*xc_id).or_insert_with(|| self.xcs.get(xc_id).cloned());
+        }
+    }
+
+    pub(super) fn record_port_id(&mut self, port_id: &PortID) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.portid_to_xcid.entry(*port_id).or_insert_with(|| self.portid_to_xcid.get(port_id).copied());
+        }
+    }
+
+    pub(super) fn record_working_route(&mut self, demand_index: usize) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.working_routes.entry(demand_index).or_insert_with(|| self.working_routes.get(&demand_index).cloned());
+        }
+    }
+
+    pub(super) fn record_layer_topologies(&mut self) {
+        if let Some(journal) = self.journal.as_mut() {
+            if journal.layer_topologies.is_none() {
+                journal.layer_topologies = Some(self.layer_topologies.clone());
+            }
+        }
+    }
+
+    pub(super) fn record_xc_ids_on_nodes(&mut self) {
+        if let Some(journal) = self.journal.as_mut() {
+            if journal.xc_ids_on_nodes.is_none() {
+                journal.xc_ids_on_nodes = Some(self.xc_ids_on_nodes.clone());
+            }
+        }
+    }
+
+    pub(super) fn record_failures(&mut self) {
+        if let Some(journal) = self.journal.as_mut() {
+            if journal.failed_edges.is_none() {
+                journal.failed_edges = Some(self.failed_edges.clone());
+                journal.failed_xc_ids = Some(self.failed_xc_ids.clone());
+            }
+        }
+    }
+}
+
+#[test]
+fn rollback_test() {
+    use crate::{config::test_config, topology::Topology};
+
+    let config = test_config();
+    let topology = Topology::new(&config);
+    let mut network = Network::new(&config, &topology, &[XCType::Wxc, XCType::Fxc]);
+
+    let edge = network.edges[0];
+    let fiber_id = network.get_fiber_id_on_edge(&edge)[0];
+    let wxc_size = network.get_xc_on_node(edge.src.into(), &XCType::Wxc).unwrap().get_size();
+
+    // 取り消した変更は残らない
+    network.begin();
+    network.delete_fiber(&config, &fiber_id);
+    assert_eq!(network.get_fibers().values().count(), topology.edges.len() - 1);
+    network.rollback();
+    assert!(!network.in_transaction());
+    assert_eq!(network.get_fibers().values().count(), topology.edges.len());
+    assert_eq!(network.get_fiber_id_on_edge(&edge), vec![fiber_id]);
+    assert_eq!(network.get_fiber_ids_on_edge_empty(&edge), vec![fiber_id]);
+    assert_eq!(network.get_xc_on_node(edge.src.into(), &XCType::Wxc).unwrap().get_size(), wxc_size);
+
+    // 確定した変更は残る
+    network.begin();
+    network.delete_fiber(&config, &fiber_id);
+    network.commit();
+    assert!(network.get_fiber_id_on_edge(&edge).is_empty());
+    assert_eq!(network.get_fibers().values().count(), topology.edges.len() - 1);
+}
@@ -1,11 +1,12 @@
 use fxhash::{FxHashMap, FxHashSet};
 use petgraph::{graph::NodeIndex, Graph};
+use serde_derive::{ Deserialize, Serialize };
 
 use crate::{demand::Demand, topology::{self, RouteCandidate}, Edge, Node, SD};
 
 use super::{Network, XCType};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerTopology {
     xc_type: XCType, // Type of Layer
     pub route_cands: FxHashMap<SD, Vec<RouteCandidate>>,
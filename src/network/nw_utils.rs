@@ -1,10 +1,10 @@
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 use crate::{config::Config, controller::expander::{expand_wxc_fibers}, network::FiberID, topology::Topology, Edge};
 
-use super::{EdgesType, Network, XCType, XC};
+use super::{arena::Arena, crosstalk::CrosstalkModel, ids::IdCounters, EdgesType, Network, XCType};
 
 pub fn network_from_hashmap(
     config: &Config,
@@ -14,12 +14,12 @@ pub fn network_from_hashmap(
     
     // Network::newとは初期ファイバ数の違いがある
 
-    let fibers = FxHashMap::default();
+    let fibers = Arena::default();
     let fiber_id_on_edges: FxHashMap<Edge, Vec<FiberID>> = FxHashMap::default();
 
     let edges = topology.edges.clone();
 
-    let xcs = FxHashMap::default();
+    let xcs = Arena::default();
 
     let edge_costs = FxHashMap::default();
 
@@ -27,21 +27,29 @@ pub fn network_from_hashmap(
     for edge in &edges {
         empty_fiber_ids_on_edges_cache.insert(*edge, vec![]);
     }
-    let portid_to_xcid = FxHashMap::default();
+    let portid_to_xcid = Arena::default();
 
     let rng = ChaCha8Rng::seed_from_u64(config.simulation.random_seed);
     let layer_topologies = FxHashMap::default();
+    let crosstalk_model = CrosstalkModel::new(config);
 
     let mut network = Network {
         fibers,
         fiber_ids_on_edges: fiber_id_on_edges,
         edges,
         xcs,
+        xc_ids_on_nodes: vec![],
         edge_costs,
         empty_fiber_ids_on_edges_cache,
         rng,
         portid_to_xcid,
         layer_topologies,
+        crosstalk_model,
+        working_routes: FxHashMap::default(),
+        failed_edges: FxHashSet::default(),
+        failed_xc_ids: FxHashSet::default(),
+        id_counters: IdCounters::default(),
+        journal: None,
         // original_wxc2wxc_fiber_count: 0,
     };
 
@@ -64,16 +72,12 @@ pub fn wxc_network_from_hashmap(
     topology: &Topology,
     hashmap: FxHashMap<(EdgesType, Vec<Edge>), usize>
 ) -> Network {
-    let fibers = FxHashMap::default();
+    let fibers = Arena::default();
     let fiber_id_on_edges: FxHashMap<Edge, Vec<FiberID>> = FxHashMap::default();
 
     let edges = topology.edges.clone();
 
-    let mut xcs = FxHashMap::default();
-    for node in 0..topology.link_matrix.len() {
-        let wxc = XC::new(node, XCType::Wxc);
-        xcs.insert(wxc.id, wxc);
-    }
+    let xcs = Arena::default();
 
     let edge_costs = FxHashMap::default();
 
@@ -83,20 +87,31 @@ pub fn wxc_network_from_hashmap(
     }
 
     let rng = ChaCha8Rng::seed_from_u64(config.simulation.random_seed);
-    let portid_to_xcid = FxHashMap::default();
+    let portid_to_xcid = Arena::default();
     let layer_topologies = FxHashMap::default();
+    let crosstalk_model = CrosstalkModel::new(config);
 
     let mut network = Network {
         fibers,
         fiber_ids_on_edges: fiber_id_on_edges,
         edges,
         xcs,
+        xc_ids_on_nodes: vec![],
         edge_costs,
         empty_fiber_ids_on_edges_cache,
         rng,
         portid_to_xcid,
         layer_topologies,
+        crosstalk_model,
+        working_routes: FxHashMap::default(),
+        failed_edges: FxHashSet::default(),
+        failed_xc_ids: FxHashSet::default(),
+        id_counters: IdCounters::default(),
+        journal: None,
     };
+    for node in 0..topology.link_matrix.len() {
+        network.get_xc_mut_on_node(node, &XCType::Wxc);
+    }
 
     for (edge_seq, count) in &hashmap {
         for _ in 0..*count {
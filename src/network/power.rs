@@ -0,0 +1,76 @@
+use crate::{config::Config, demand::Demand, np_core::parameters::WAVEBAND_COUNT};
+
+use super::{wss::calc_route_and_select_wss_count, Network, XCType, XC};
+
+/// XCごとの消費電力
+#[derive(Debug, Clone, Copy)]
+pub struct XcPower {
+    pub node: usize,
+    pub xc_type: XCType,
+    /// ポート数 (`XC::get_size`)
+    pub ports: usize,
+    /// スイッチ素子の数 (WXC: WSS，FXC・SXC: ポート，WBXC: 波長帯スイッチ)
+    pub switches: usize,
+    /// 消費電力 [W]
+    pub power: f64,
+}
+
+impl XC {
+    /// スイッチ素子の数
+    pub fn count_switches(&self, config: &Config) -> usize {
+        let size = self.get_size();
+        match self.xc_type {
+            XCType::Wxc | XCType::Added_Wxc => {
+                if size == 0 {
+                    0
+                } else {
+                    calc_route_and_select_wss_count(size, config.network.wss_m)
+                }
+            }
+            XCType::Fxc | XCType::Sxc => size,
+            XCType::Wbxc => size * WAVEBAND_COUNT,
+        }
+    }
+
+    /// 静的な消費電力 [W]
+    pub fn calc_power(&self, config: &Config) -> f64 {
+        let power = &config.power;
+        let size = self.get_size() as f64;
+        let switches = self.count_switches(config) as f64;
+        match self.xc_type {
+            XCType::Wxc | XCType::Added_Wxc => switches * power.wss + size * power.wxc_port,
+            XCType::Fxc | XCType::Sxc => size * power.fxc_port,
+            XCType::Wbxc => switches * power.wbxc_waveband_switch,
+        }
+    }
+}
+
+/// トランスポンダの消費電力 [W]
+pub fn calc_transponder_power(config: &Config, demand_list: &[Demand]) -> f64 {
+    2.0 * demand_list.len() as f64 * config.power.transponder
+}
+
+impl Network {
+    /// ポートを持つXCの消費電力を，ノード順に返す
+    pub fn get_xc_powers(&self, config: &Config) -> Vec<XcPower> {
+        let mut powers: Vec<XcPower> = self.xcs
+            .values()
+            .filter(|xc| xc.get_size() > 0)
+            .map(|xc| XcPower {
+                node: xc.node,
+                xc_type: xc.xc_type,
+                ports: xc.get_size(),
+                switches: xc.count_switches(config),
+                power: xc.calc_power(config),
+            })
+            .collect();
+        powers.sort_by_key(|power| (power.node, power.xc_type as usize));
+        powers
+    }
+
+    /// XCとトランスポンダの消費電力の合計 [W]
+    pub fn calc_total_power(&self, config: &Config, demand_list: &[Demand]) -> f64 {
+        let xc_power: f64 = self.get_xc_powers(config).iter().map(|power| power.power).sum();
+        xc_power + calc_transponder_power(config, demand_list)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{ config::Config, topology::{ DisjointType, Topology }, Edge };
+use serde_derive::{ Deserialize, Serialize };
+
+use super::{ CoreIndex, FiberID, Network, XCID };
+
+/// パス保護方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectionMode {
+    /// 保護なし
+    Unprotected,
+    /// 1+1 専用パス保護
+    Dedicated,
+    /// 共有予備パス保護 (現用パスが独立な予備パス同士でスロットを共有する)
+    Shared,
+}
+
+impl ProtectionMode {
+    pub fn from_config(config: &Config) -> Self {
+        match config.protection.mode.to_uppercase().as_str() {
+            "NONE" => ProtectionMode::Unprotected,
+            "DEDICATED" | "1+1" => ProtectionMode::Dedicated,
+            "SHARED" | "SBPP" => ProtectionMode::Shared,
+            _ => panic!("Invalid protection `mode`: {}", config.protection.mode),
+        }
+    }
+}
+
+pub fn disjoint_type_from_config(config: &Config) -> DisjointType {
+    match config.protection.disjoint_type.to_uppercase().as_str() {
+        "LINK" => DisjointType::Link,
+        "NODE" => DisjointType::Node,
+        _ => panic!("Invalid `disjoint_type`: {}", config.protection.disjoint_type),
+    }
+}
+
+/// 同時に故障しうる設備の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RiskGroup {
+    /// 物理リンク (方向は区別しない)
+    Link(Edge),
+    /// SRLG (トポロジのSRLG番号)
+    Srlg(usize),
+    /// ノード (ノード上のすべてのXC)
+    Node(usize),
+    /// XC
+    Xc(XCID),
+}
+
+/// 経路上のリンクのリスクグループ
+pub fn get_link_risk_groups(edge_route: &[Edge]) -> Vec<RiskGroup> {
+    let mut risk_groups: Vec<RiskGroup> = edge_route
+        .iter()
+        .map(|edge| {
+            let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+            RiskGroup::Link(Edge::new(src.min(dst), src.max(dst)))
+        })
+        .collect();
+    risk_groups.sort();
+    risk_groups.dedup();
+    risk_groups
+}
+
+/// 経路上のリンクおよび経路が通過するSRLGのリスクグループ
+pub fn get_risk_groups(topology: &Topology, edge_route: &[Edge]) -> Vec<RiskGroup> {
+    let mut risk_groups = get_link_risk_groups(edge_route);
+    risk_groups.extend(
+        topology
+            .get_srlg_ids_on_route(edge_route)
+            .into_iter()
+            .map(RiskGroup::Srlg),
+    );
+    risk_groups
+}
+
+/// 現用パスのファイバルートが経由する中継ノードと，ファイバが接続するXCのリスクグループ
+/// 始点・終点ノードが故障した場合は予備パスも断となり切り替えないため，ノードは中継ノードのみとする
+pub fn get_equipment_risk_groups(network: &Network, fiber_ids: &[FiberID]) -> Vec<RiskGroup> {
+    let mut risk_groups = vec![];
+    for (i, fiber_id) in fiber_ids.iter().enumerate() {
+        let fiber = network.get_fiber_by_id(fiber_id);
+        if i > 0 {
+            risk_groups.push(RiskGroup::Node(fiber.edge.src.into()));
+        }
+
+        // MCFのポートはすべて同じXCに属するため，先頭のポートのみ確認する
+        for port_id in fiber.src_port_ids.first().into_iter().chain(fiber.dst_port_ids.first()) {
+            risk_groups.push(RiskGroup::Xc(*network.portid_to_xcid.get(port_id).unwrap()));
+        }
+    }
+    risk_groups.sort();
+    risk_groups.dedup();
+    risk_groups
+}
+
+/// 共通のリスクグループを持たないか
+pub fn is_risk_disjoint(a: &[RiskGroup], b: &[RiskGroup]) -> bool {
+    a.iter().all(|risk_group| !b.contains(risk_group))
+}
+
+/// 予備パスの割当情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupPath {
+    pub fiber_ids: Vec<FiberID>,
+    pub slot_heads: Vec<usize>,
+    pub core_indices: Vec<CoreIndex>,
+    /// 共有予備パスの場合，保護対象の現用パスのリスクグループ
+    /// 専用予備パスの場合は`None`
+    pub shared_risk_groups: Option<Vec<RiskGroup>>,
+}
+
+/// ファイバ上の共有予備スロットの予約
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupReservation {
+    pub demand_id: usize,
+    /// 保護対象の現用パスのリスクグループ
+    pub risk_groups: Vec<RiskGroup>,
+}
@@ -0,0 +1,208 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{demand::Demand, np_core::parameters::SLOT};
+
+use super::{Network, PortID, XCID};
+
+impl Network {
+    /// NWの不変条件を検証し，違反の一覧を返す (違反がなければ空)
+    ///
+    /// 不整合は後になって`XC::remove_device`や`Fiber::assign`などの`panic!()`として現れるため，
+    /// 変更の直後に呼ぶことで原因を特定しやすくする．
+    /// `demand_list`はNWに割り当て済みのパス需要をすべて含む必要がある．
+    pub fn validate(&self, demand_list: &[Demand]) -> Vec<String> {
+        let mut violations = vec![];
+        let port_owners = self.validate_ports(&mut violations);
+        self.validate_connections(&mut violations);
+        self.validate_fibers(&port_owners, &mut violations);
+        self.validate_assigned_demands(demand_list, &mut violations);
+        self.validate_fiber_ids_on_edges(&mut violations);
+        violations
+    }
+
+    /// 各ポートがちょうど1つのXCに属し，`portid_to_xcid`に登録されているか
+    ///
+    /// ポートID -> (所属するXC, 入力ポートか) を返す
+    fn validate_ports(&self, violations: &mut Vec<String>) -> FxHashMap<PortID, (XCID, bool)> {
+        let mut port_owners: FxHashMap<PortID, (XCID, bool)> = FxHashMap::default();
+
+        for (xc_id, xc) in self.xcs.iter() {
+            let devices = xc.get_input_device_ids()
+                .map(|port_id| (port_id, true))
+                .chain(xc.get_output_device_ids().map(|port_id| (port_id, false)));
+            for (port_id, is_input) in devices {
+                if let Some((owner_xc_id, _)) = port_owners.insert(*port_id, (xc_id, is_input)) {
+                    violations.push(format!("Port {port_id} is owned by both {owner_xc_id:?} and {xc_id:?}"));
+                }
+
+                match self.portid_to_xcid.get(port_id) {
+                    Some(registered_xc_id) if *registered_xc_id == xc_id => (),
+                    Some(registered_xc_id) => violations.push(format!(
+                        "Port {port_id} of {xc_id:?} is registered to {registered_xc_id:?}"
+                    )),
+                    None => violations.push(format!("Port {port_id} of {xc_id:?} is not registered")),
+                }
+            }
+        }
+
+        // 削除されたポートの登録は残るため，登録先のXCが存在することのみ確認する
+        for (port_id, xc_id) in self.portid_to_xcid.iter() {
+            if !self.xcs.contains_key(xc_id) {
+                violations.push(format!("Port {port_id} is registered to nonexistent {xc_id:?}"));
+            }
+        }
+
+        port_owners
+    }
+
+    /// XCの接続表が，そのXCの入力ポートと出力ポートを参照しているか
+    fn validate_connections(&self, violations: &mut Vec<String>) {
+        for (xc_id, xc) in self.xcs.iter() {
+            for (input_port_id, output_port_id) in xc.get_connections() {
+                if !xc.has_input_device(&input_port_id) {
+                    violations.push(format!("{xc_id:?} connects nonexistent input port {input_port_id}"));
+                }
+                if !xc.has_output_device(&output_port_id) {
+                    violations.push(format!("{xc_id:?} connects nonexistent output port {output_port_id}"));
+                }
+            }
+        }
+    }
+
+    /// ファイバの使用スロット数が`state_matrixes`と一致し，両端のポートが存在するか
+    fn validate_fibers(&self, port_owners: &FxHashMap<PortID, (XCID, bool)>, violations: &mut Vec<String>) {
+        for (fiber_id, fiber) in self.fibers.iter() {
+            let used_slot_count = fiber.state_matrixes
+                .iter()
+                .map(|state_matrix| state_matrix.iter().filter(|s| **s).count())
+                .sum::<usize>();
+            if fiber.occupancy != used_slot_count {
+                violations.push(format!(
+                    "Fiber {fiber_id}: occupancy is {} but {used_slot_count} slots are used",
+                    fiber.occupancy
+                ));
+            }
+            if fiber.total_slots() != fiber.get_core_num() * SLOT {
+                violations.push(format!(
+                    "Fiber {fiber_id}: occupancy + residual is {} but it has {} slots",
+                    fiber.total_slots(), fiber.get_core_num() * SLOT
+                ));
+            }
+
+            let ports = fiber.src_port_ids
+                .iter()
+                .map(|port_id| (port_id, false))
+                .chain(fiber.dst_port_ids.iter().map(|port_id| (port_id, true)));
+            for (port_id, is_input) in ports {
+                match port_owners.get(port_id) {
+                    Some((_, owner_is_input)) if *owner_is_input == is_input => (),
+                    Some(_) => violations.push(format!("Fiber {fiber_id}: port {port_id} has the wrong direction")),
+                    None => violations.push(format!("Fiber {fiber_id}: port {port_id} is not owned by any XC")),
+                }
+            }
+        }
+    }
+
+    /// ファイバの`assigned_demand_ids`が，パス需要の (現用・予備) ファイバルートと一致するか
+    fn validate_assigned_demands(&self, demand_list: &[Demand], violations: &mut Vec<String>) {
+        let mut expected: FxHashMap<_, FxHashSet<usize>> = FxHashMap::default();
+        for demand in demand_list {
+            for fiber_id in demand.get_fiber_routes().into_iter().flatten() {
+                if !self.fibers.contains_key(fiber_id) {
+                    violations.push(format!("Demand {} uses nonexistent fiber {fiber_id}", demand.index));
+                    continue;
+                }
+                expected.entry(*fiber_id).or_default().insert(demand.index);
+            }
+        }
+
+        for (fiber_id, fiber) in self.fibers.iter() {
+            let expected_demand_ids = expected.remove(&fiber_id).unwrap_or_default();
+            for demand_id in expected_demand_ids.difference(&fiber.assigned_demand_ids) {
+                violations.push(format!("Demand {demand_id} uses fiber {fiber_id} but is not assigned to it"));
+            }
+            for demand_id in fiber.assigned_demand_ids.difference(&expected_demand_ids) {
+                violations.push(format!("Demand {demand_id} is assigned to fiber {fiber_id} but does not use it"));
+            }
+        }
+    }
+
+    /// `fiber_ids_on_edges`と空きのあるファイバのキャッシュが，ファイバと一致するか
+    fn validate_fiber_ids_on_edges(&self, violations: &mut Vec<String>) {
+        let mut fiber_ids_on_edges: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        for (fiber_id, fiber) in self.fibers.iter() {
+            fiber_ids_on_edges.entry(fiber.edge).or_default().push(fiber_id);
+        }
+
+        let mut edges: Vec<_> = self.fiber_ids_on_edges
+            .keys()
+            .chain(self.empty_fiber_ids_on_edges_cache.keys())
+            .chain(fiber_ids_on_edges.keys())
+            .copied()
+            .collect::<FxHashSet<_>>()
+            .into_iter()
+            .collect();
+        edges.sort();
+
+        for edge in edges {
+            let mut fiber_ids = fiber_ids_on_edges.remove(&edge).unwrap_or_default();
+            fiber_ids.sort();
+            let mut registered_fiber_ids = self.get_fiber_id_on_edge(&edge);
+            registered_fiber_ids.sort();
+            if registered_fiber_ids != fiber_ids {
+                violations.push(format!(
+                    "Edge {edge}: fiber_ids_on_edges is {registered_fiber_ids:?} but the fibers are {fiber_ids:?}"
+                ));
+            }
+
+            fiber_ids.retain(|fiber_id| !self.fibers.get(fiber_id).unwrap().is_full());
+            let mut cached_fiber_ids = self.empty_fiber_ids_on_edges_cache
+                .get(&edge)
+                .cloned()
+                .unwrap_or_default();
+            cached_fiber_ids.sort();
+            if cached_fiber_ids != fiber_ids {
+                violations.push(format!(
+                    "Edge {edge}: empty fiber cache is {cached_fiber_ids:?} but the non-full fibers are {fiber_ids:?}"
+                ));
+            }
+        }
+    }
+}
+
+#[test]
+fn validate_test() {
+    use crate::{config::test_config, network::{CoreIndex, XCType}, topology::Topology, SD};
+
+    let config = test_config();
+    let topology = Topology::new(&config);
+    let mut network = Network::new(&config, &topology, &[XCType::Wxc, XCType::Fxc]);
+    assert!(network.validate(&[]).is_empty());
+
+    let edge = network.edges[0];
+    let fiber_id = network.get_fiber_id_on_edge(&edge)[0];
+    let mut demand = Demand::new(SD::new(edge.src.into(), edge.dst.into()), 0, 0, 1);
+
+    // パス需要に記録されていない割当
+    network.get_fiber_mut_by_id(&fiber_id).assign(0, 1, &CoreIndex::new(0), demand.index);
+    assert_eq!(network.validate(&[demand.clone()]).len(), 1);
+
+    demand.fiber_ids.push(fiber_id);
+    assert!(network.validate(&[demand.clone()]).is_empty());
+
+    // 使用スロット数とstate_matrixesの不一致
+    let fiber = network.get_fiber_mut_by_id(&fiber_id);
+    fiber.occupancy += 1;
+    fiber.residual -= 1;
+    assert_eq!(network.validate(&[demand.clone()]).len(), 1);
+
+    // 総スロット数の不一致
+    network.get_fiber_mut_by_id(&fiber_id).occupancy -= 1;
+    assert_eq!(network.validate(&[demand.clone()]).len(), 1);
+    network.get_fiber_mut_by_id(&fiber_id).residual += 1;
+
+    // 削除されたポートを使用するファイバ
+    let src_port_id = network.get_fiber_by_id(&fiber_id).src_port_ids[0];
+    network.get_xc_mut_by_output_port_id(&src_port_id).remove_device(src_port_id, false);
+    assert_eq!(network.validate(&[demand]).len(), 1);
+}
@@ -0,0 +1,99 @@
+use super::{Network, XCType};
+
+/// 1xNのスイッチを1xMのWSSの多段接続 (木構造) で構成する場合の段数
+pub fn calc_wss_stages(degree: usize, wss_m: usize) -> usize {
+    assert!(wss_m >= 2, "`wss_m` needs to be larger than 1");
+
+    let mut stages = 1;
+    let mut fan_out = wss_m;
+    while fan_out < degree {
+        fan_out *= wss_m;
+        stages += 1;
+    }
+    stages
+}
+
+/// 1xNのスイッチを1xMのWSSの多段接続 (木構造) で構成する場合のWSSの個数
+pub fn calc_wss_count(degree: usize, wss_m: usize) -> usize {
+    assert!(wss_m >= 2, "`wss_m` needs to be larger than 1");
+
+    if degree <= wss_m {
+        return 1;
+    }
+    let last_stage = degree.div_ceil(wss_m);
+    last_stage + calc_wss_count(last_stage, wss_m)
+}
+
+/// Route and Select構成のXC全体のWSSの個数
+/// 入出力ポートごとに1xNのスイッチを置く
+pub fn calc_route_and_select_wss_count(degree: usize, wss_m: usize) -> usize {
+    2 * degree * calc_wss_count(degree, wss_m)
+}
+
+/// WSSで構成するXC (WXC，補助WXC) の構成
+///
+/// Route and Select構成とし，入出力ポートごとに1xNのスイッチを置く．Add/Dropポートは考慮しない．
+#[derive(Debug, Clone, Copy)]
+pub struct WssUsage {
+    pub node: usize,
+    pub xc_type: XCType,
+    /// 方路数 (`XC::get_size`)
+    pub degree: usize,
+    /// 1xNのスイッチあたりのWSSの段数
+    pub stages: usize,
+    /// XC全体のWSSの個数
+    pub wss_count: usize,
+}
+
+impl WssUsage {
+    /// 1xMのWSSを多段接続する必要があるかどうか
+    pub fn needs_cascade(&self) -> bool {
+        self.stages > 1
+    }
+}
+
+impl Network {
+    /// WXCと補助WXCのWSSの構成を，ノード順に返す
+    pub fn get_wss_usages(&self, wss_m: usize) -> Vec<WssUsage> {
+        let mut usages: Vec<WssUsage> = self.xcs
+            .values()
+            .filter(|xc| matches!(xc.xc_type, XCType::Wxc | XCType::Added_Wxc))
+            .filter(|xc| xc.get_size() > 0)
+            .map(|xc| {
+                let degree = xc.get_size();
+                WssUsage {
+                    node: xc.node,
+                    xc_type: xc.xc_type,
+                    degree,
+                    stages: calc_wss_stages(degree, wss_m),
+                    wss_count: calc_route_and_select_wss_count(degree, wss_m),
+                }
+            })
+            .collect();
+        usages.sort_by_key(|usage| (usage.node, usage.xc_type as usize));
+        usages
+    }
+
+    /// 1xMのWSSを多段接続する必要があるXCの数
+    pub fn count_wss_cascaded_xcs(&self, wss_m: usize) -> usize {
+        self.get_wss_usages(wss_m)
+            .iter()
+            .filter(|usage| usage.needs_cascade())
+            .count()
+    }
+}
+
+#[test]
+fn wss_cascade_test() {
+    assert_eq!(calc_wss_stages(1, 4), 1);
+    assert_eq!(calc_wss_stages(4, 4), 1);
+    assert_eq!(calc_wss_stages(5, 4), 2);
+    assert_eq!(calc_wss_stages(16, 4), 2);
+    assert_eq!(calc_wss_stages(17, 4), 3);
+
+    assert_eq!(calc_wss_count(4, 4), 1);
+    // 1x4を4個並べ，その前段に1x4を1個置く
+    assert_eq!(calc_wss_count(16, 4), 5);
+    // 1x4を5個並べ，その前段に1x4を2個，さらに前段に1x4を1個置く
+    assert_eq!(calc_wss_count(17, 4), 8);
+}
@@ -1,19 +1,22 @@
 use fxhash::FxHashSet;
 use strum_macros::EnumIter;
-use uuid::Uuid;
+use serde_derive::{ Deserialize, Serialize };
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
-pub struct XCID (Uuid);
+pub struct XCID (usize);
 
-impl XCID {
-    pub fn new() -> Self {
-        XCID (Uuid::now_v7())
+impl ArenaKey for XCID {
+    fn index(&self) -> usize {
+        self.0
+    }
+    fn from_index(index: usize) -> Self {
+        XCID (index)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct XC {
     pub xc_type: XCType,
     pub id: XCID,
@@ -25,10 +28,10 @@ pub struct XC {
 }
 
 impl XC {
-    pub fn new(node: usize, xc_type: XCType) -> Self {
+    pub fn new(id: XCID, node: usize, xc_type: XCType) -> Self {
         Self {
             xc_type,
-            id: XCID::new(),
+            id,
             node,
             input_devices: FxHashSet::default(),
             output_devices: FxHashSet::default(),
@@ -41,8 +44,8 @@ impl XC {
         max(self.input_devices.len(), self.output_devices.len())
     }
 
-    pub fn generate_new_device(&mut self, is_input: bool) -> PortID {
-        let id = PortID(Uuid::now_v7());
+    /// `Network::generate_port_id`で発行したIDのポートを追加する
+    pub fn generate_new_device(&mut self, id: PortID, is_input: bool) -> PortID {
         if is_input {
             self.input_devices.insert(id);
         } else {
@@ -51,6 +54,7 @@ impl XC {
         id
     }
 
+    /// ポートを削除する．ポートを参照する接続も合わせて削除する
     pub fn remove_device(&mut self, target_device_id: PortID, is_input: bool) {
         if is_input {
             if self.has_input_device(&target_device_id) {
@@ -63,6 +67,12 @@ impl XC {
         } else {
             panic!()
         }
+
+        let port_index = if is_input { 0 } else { 1 };
+        self.fiber_connection_martrix.retain(|p| p[port_index] != target_device_id);
+        self.waveband_connection_matrix.retain(|(i_id, o_id, _wb)| {
+            if is_input { *i_id != target_device_id } else { *o_id != target_device_id }
+        });
     }
 
     pub fn has_input_device(&self, input_device_id: &PortID) -> bool {
@@ -72,6 +82,22 @@ impl XC {
         self.output_devices.contains(output_device_id)
     }
 
+    pub fn get_input_device_ids(&self) -> impl Iterator<Item = &PortID> {
+        self.input_devices.iter()
+    }
+    pub fn get_output_device_ids(&self) -> impl Iterator<Item = &PortID> {
+        self.output_devices.iter()
+    }
+
+    /// 接続表に登録されている (入力ポート, 出力ポート) の組 (WBXCではWBを区別しない)
+    pub fn get_connections(&self) -> Vec<(PortID, PortID)> {
+        self.fiber_connection_martrix
+            .iter()
+            .map(|[i_id, o_id]| (*i_id, *o_id))
+            .chain(self.waveband_connection_matrix.iter().map(|(i_id, o_id, _wb)| (*i_id, *o_id)))
+            .collect()
+    }
+
     pub fn is_input_device_wb_occupied(&self, input_device_id: &PortID, waveband: &WBIndex) -> bool {
         self.waveband_connection_matrix.iter().any(|(i_id, _o_id, wb)| {
             *i_id == *input_device_id && *wb == *waveband
@@ -88,7 +114,8 @@ impl XC {
             XCType::Wxc => true,
             XCType::Added_Wxc => true,
             XCType::Fxc | XCType::Sxc => self.fiber_connection_martrix.contains(&[*input_device_id, *output_device_id]),
-            XCType::Wbxc => todo!("WBXC用のテーブルを作成する必要"),
+            // いずれかのWBで接続されていれば通過できる，WBの一致はcan_route_wbで確認する
+            XCType::Wbxc => self.waveband_connection_matrix.iter().any(|(i_id, o_id, _wb)| i_id == input_device_id && o_id == output_device_id),
         }
     }
 
@@ -293,7 +320,7 @@ impl XC {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum XCType {
     Wxc = 0,
     Wbxc = 1,
@@ -302,6 +329,9 @@ pub enum XCType {
     Added_Wxc = 4
 }
 
+/// `XCType`の種類数 (ノードごとのXCの表の大きさ)
+pub const XC_TYPE_COUNT: usize = 5;
+
 pub fn xc_type_to_quality_distance(xc_type: XCType) -> usize {
     match xc_type {
         XCType::Wxc => WXC_PORT_Q_DISTANCE,
@@ -316,6 +346,8 @@ use std::{cmp::max, fmt};
 
 use crate::{np_core::parameters::{ FXC_PORT_Q_DISTANCE, WXC_PORT_Q_DISTANCE }, WBIndex};
 
+use super::arena::ArenaKey;
+
 impl fmt::Display for XCType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // フォーマットしたい内容を定義
@@ -329,11 +361,20 @@ impl fmt::Display for XCType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct PortID (Uuid);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PortID (usize);
+impl ArenaKey for PortID {
+    fn index(&self) -> usize {
+        self.0
+    }
+    fn from_index(index: usize) -> Self {
+        PortID(index)
+    }
+}
 impl PortID {
+    /// どのポートも指さないID
     pub(crate) fn nil() -> PortID {
-        PortID(Uuid::nil())
+        PortID(usize::MAX)
     }
 }
 
@@ -1,9 +1,10 @@
 use std::fmt::Display;
+use serde_derive::{ Deserialize, Serialize };
 
 use super::Node;
 
 /// Represents an undirected edge between two nodes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Edge {
     /// The first node connected by the edge.
     pub src: Node,
@@ -1,9 +1,9 @@
 use std::fmt::Display;
 
-use serde_derive::Deserialize;
+use serde_derive::{ Deserialize, Serialize };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Node {
     pub(super) value: usize,
 }
@@ -1,9 +1,10 @@
 use std::fmt::Display;
+use serde_derive::{ Deserialize, Serialize };
 
 use super::Node;
 
 /// Represents a directed link between two nodes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SD {
     /// The source node of the directed link.
     pub src: Node,
@@ -1,5 +1,7 @@
 use std::{fmt::Display, ops::{ BitAnd, BitAndAssign, BitOr, BitOrAssign, Index, IndexMut, Range }};
 
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
 use super::{parameters::SLOT, WBIndex};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,6 +77,23 @@ impl IndexMut<Range<usize>> for StateMatrix {
     }
 }
 
+// serdeは長さ32を超える配列に対応していないため，スライスとして読み書きする
+impl Serialize for StateMatrix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0[..].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateMatrix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let slots = Vec::<bool>::deserialize(deserializer)?;
+        let slots: [bool; SLOT] = slots
+            .try_into()
+            .map_err(|slots: Vec<bool>| D::Error::invalid_length(slots.len(), &"SLOT booleans"))?;
+        Ok(StateMatrix(slots))
+    }
+}
+
 impl StateMatrix {
     pub fn new() -> StateMatrix {
         Self([false; SLOT])
@@ -169,4 +188,18 @@ impl Display for StateMatrix {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[test]
+fn serde_roundtrip_test() {
+    let mut state_matrix = StateMatrix::new();
+    state_matrix[0] = true;
+    state_matrix[SLOT - 1] = true;
+
+    let bytes = bincode::serialize(&state_matrix).unwrap();
+    let restored: StateMatrix = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(restored, state_matrix);
+
+    // 長さの異なる配列は読み込めない
+    let bytes = bincode::serialize(&vec![false; SLOT - 1]).unwrap();
+    assert!(bincode::deserialize::<StateMatrix>(&bytes).is_err());
+}
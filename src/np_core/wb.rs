@@ -1,6 +1,7 @@
 use super::parameters::{SLOT, WAVEBAND_COUNT};
+use serde_derive::{ Deserialize, Serialize };
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct WBIndex (usize);
 
 impl WBIndex {
@@ -1,14 +1,81 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use petgraph::{ algo::all_simple_paths, graph::{ Graph, NodeIndex } };
-use rayon::{iter::{IntoParallelIterator, ParallelIterator}, ThreadPoolBuilder};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::{ cmp::max, fs::File, io::Read };
 use itertools::iproduct;
+use serde_derive::{ Deserialize, Serialize };
 
-use crate::{ config::Config, np_core::parameters::{ HOP_SLUG, PB_CHARS, PB_TEMPLATES, SHORTEST_K, THREADS }, utils::{self, shuffle_array}, Edge, SD };
+use crate::{ config::Config, np_core::parameters::{ HOP_SLUG, PB_CHARS, PB_TEMPLATES, SHORTEST_K }, utils::{self, shuffle_array}, Edge, SD };
 
 use fxhash::FxHashMap;
 
+mod disjoint;
+mod ksp;
+
+pub use disjoint::DisjointType;
+
+/// 経路候補の探索手法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteSearchMethod {
+    /// Yenのk最短経路
+    /// 同じホップ数の経路の順序がall_simple_pathsと異なるため，`k`で打ち切ると経路候補が変わりうる
+    Yen,
+    /// petgraph::all_simple_pathsによる全列挙 (既定)
+    AllSimplePaths,
+}
+
+/// 経路の重み付け
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteWeight {
+    Hop,
+    Distance,
+}
+
+/// 経路候補探索の設定
 #[derive(Debug, Clone)]
+pub struct RouteSearchSetting {
+    pub method: RouteSearchMethod,
+    pub weight: RouteWeight,
+    /// SDペアあたりの経路候補数の上限
+    pub k: usize,
+    /// 最短経路のホップ数から許容する追加ホップ数
+    pub hop_slack: usize,
+}
+
+impl RouteSearchSetting {
+    pub fn from_config(config: &Config) -> Self {
+        let method = match config.route.search_method.to_uppercase().as_str() {
+            "YEN" => RouteSearchMethod::Yen,
+            "ALL_SIMPLE_PATHS" => RouteSearchMethod::AllSimplePaths,
+            _ => panic!("Invalid `search_method`: {}", config.route.search_method),
+        };
+        let weight = match config.route.weight.to_uppercase().as_str() {
+            "HOP" => RouteWeight::Hop,
+            "DISTANCE" => RouteWeight::Distance,
+            _ => panic!("Invalid `weight`: {}", config.route.weight),
+        };
+
+        RouteSearchSetting {
+            method,
+            weight,
+            k: config.route.shortest_k,
+            hop_slack: config.route.hop_slack,
+        }
+    }
+}
+
+impl Default for RouteSearchSetting {
+    fn default() -> Self {
+        RouteSearchSetting {
+            method: RouteSearchMethod::AllSimplePaths,
+            weight: RouteWeight::Hop,
+            k: SHORTEST_K,
+            hop_slack: HOP_SLUG,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteCandidate {
     pub node_route: Vec<usize>,
     pub edge_route: Vec<Edge>,
@@ -20,6 +87,28 @@ impl RouteCandidate {
             edge_route,
         }
     }
+
+    /// 他の経路と独立か (リンク独立の場合は逆方向のリンクも共有しない)
+    pub fn is_disjoint_with(&self, other: &RouteCandidate, disjoint_type: DisjointType) -> bool {
+        match disjoint_type {
+            DisjointType::Link => self.edge_route.iter().all(|edge| {
+                let reversed = Edge::new(edge.dst.into(), edge.src.into());
+                !other.edge_route.contains(edge) && !other.edge_route.contains(&reversed)
+            }),
+            DisjointType::Node => {
+                let inner_nodes = &self.node_route[1..self.node_route.len() - 1];
+                inner_nodes.iter().all(|node| !other.node_route.contains(node))
+                    && self.edge_route.iter().all(|edge| !other.edge_route.contains(edge))
+            }
+        }
+    }
+}
+
+/// 独立な経路対 (主経路のコストが副経路以下)
+#[derive(Debug, Clone)]
+pub struct DisjointRoutePair {
+    pub primary: RouteCandidate,
+    pub secondary: RouteCandidate,
 }
 
 #[derive(Debug)]
@@ -32,6 +121,13 @@ pub struct Topology {
     pub edges: Vec<Edge>,
     /// ルート情報
     pub route_candidates: FxHashMap<SD, Vec<RouteCandidate>>,
+    /// リンク独立な経路対 (存在しないSDペアは含まない)
+    pub link_disjoint_pairs: FxHashMap<SD, DisjointRoutePair>,
+    /// ノード独立な経路対 (存在しないSDペアは含まない)
+    pub node_disjoint_pairs: FxHashMap<SD, DisjointRoutePair>,
+    /// SRLG (同じ管路などを共有し，同時に故障しうるリンクの集合)
+    /// インデックスがSRLG番号．リンクは始点 < 終点で正規化する
+    pub srlgs: Vec<Vec<Edge>>,
 }
 
 impl Topology {
@@ -40,20 +136,128 @@ impl Topology {
         let link_matrix = get_link_matrix(&name);
         let edges = link_matrix_to_edges(&link_matrix);
 
-        let route_candidates = get_route_candidates_from_matrix(&link_matrix);
+        let setting = RouteSearchSetting::from_config(config);
+        let distance_matrix = match setting.weight {
+            RouteWeight::Hop => None,
+            RouteWeight::Distance => Some(get_distance_matrix(&name)),
+        };
+
+        let route_candidates = get_route_candidates_from_matrix(&link_matrix, distance_matrix.as_deref(), &setting);
+
+        let link_disjoint_pairs = get_disjoint_route_pairs(&link_matrix, distance_matrix.as_deref(), DisjointType::Link);
+        let node_disjoint_pairs = get_disjoint_route_pairs(&link_matrix, distance_matrix.as_deref(), DisjointType::Node);
 
-        Topology {
+        let srlgs = get_srlgs(&name);
+
+        let mut topology = Topology {
             name,
             link_matrix,
             edges,
             route_candidates,
+            link_disjoint_pairs,
+            node_disjoint_pairs,
+            srlgs,
+        };
+
+        if !topology.srlgs.is_empty() {
+            topology.link_disjoint_pairs = topology.get_srlg_disjoint_route_pairs(&topology.link_disjoint_pairs, distance_matrix.as_deref(), DisjointType::Link);
+            topology.node_disjoint_pairs = topology.get_srlg_disjoint_route_pairs(&topology.node_disjoint_pairs, distance_matrix.as_deref(), DisjointType::Node);
+        }
+
+        topology
+    }
+
+    /// 経路が通過するSRLGの番号 (昇順，重複なし)
+    pub fn get_srlg_ids_on_route(&self, edge_route: &[Edge]) -> Vec<usize> {
+        let undirected_edges: Vec<Edge> = edge_route.iter().map(to_undirected).collect();
+        self.srlgs
+            .iter()
+            .enumerate()
+            .filter(|(_, srlg)| srlg.iter().any(|edge| undirected_edges.contains(edge)))
+            .map(|(srlg_id, _)| srlg_id)
+            .collect()
+    }
+
+    /// 2つの経路が共通のSRLGを通過しないか
+    pub fn is_srlg_disjoint(&self, a: &RouteCandidate, b: &RouteCandidate) -> bool {
+        let a_srlg_ids = self.get_srlg_ids_on_route(&a.edge_route);
+        self.get_srlg_ids_on_route(&b.edge_route)
+            .iter()
+            .all(|srlg_id| !a_srlg_ids.contains(srlg_id))
+    }
+
+    /// 独立な経路対のうちSRLGを共有するものを，経路候補から選んだSRLG独立な経路対で置き換える
+    ///
+    /// Suurballeのアルゴリズムは SRLG を扱えないため，経路候補の組から総コスト最小の対を選ぶ．
+    /// SRLG独立な経路対が存在しないSDペアは除外する．
+    fn get_srlg_disjoint_route_pairs(
+        &self,
+        disjoint_pairs: &FxHashMap<SD, DisjointRoutePair>,
+        distance_matrix: Option<&[Vec<usize>]>,
+        disjoint_type: DisjointType,
+    ) -> FxHashMap<SD, DisjointRoutePair> {
+        let route_cost = |route_cand: &RouteCandidate| -> usize {
+            route_cand.edge_route
+                .iter()
+                .map(|edge| {
+                    let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+                    distance_matrix.map_or(1, |d| d[src][dst])
+                })
+                .sum()
+        };
+
+        let mut srlg_disjoint_pairs = FxHashMap::default();
+        for (sd, pair) in disjoint_pairs {
+            if self.is_srlg_disjoint(&pair.primary, &pair.secondary) {
+                srlg_disjoint_pairs.insert(*sd, pair.clone());
+                continue;
+            }
+
+            let route_cands = self.route_candidates.get(sd).unwrap();
+            let mut best_pair: Option<(usize, &RouteCandidate, &RouteCandidate)> = None;
+            for (i, primary) in route_cands.iter().enumerate() {
+                for secondary in &route_cands[i + 1..] {
+                    if !primary.is_disjoint_with(secondary, disjoint_type) || !self.is_srlg_disjoint(primary, secondary) {
+                        continue;
+                    }
+
+                    let cost = route_cost(primary) + route_cost(secondary);
+                    if best_pair.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                        best_pair = Some((cost, primary, secondary));
+                    }
+                }
+            }
+
+            if let Some((_, primary, secondary)) = best_pair {
+                srlg_disjoint_pairs.insert(
+                    *sd,
+                    DisjointRoutePair {
+                        primary: primary.clone(),
+                        secondary: secondary.clone(),
+                    },
+                );
+            }
+        }
+
+        srlg_disjoint_pairs
+    }
+
+    /// SDペアの独立な経路対を返す (存在しなければ`None`)
+    pub fn get_disjoint_route_pair(&self, sd: &SD, disjoint_type: DisjointType) -> Option<&DisjointRoutePair> {
+        match disjoint_type {
+            DisjointType::Link => self.link_disjoint_pairs.get(sd),
+            DisjointType::Node => self.node_disjoint_pairs.get(sd),
         }
     }
 }
 
 
 
-fn get_route_candidates_from_matrix(link_matrix: &[Vec<bool>]) -> FxHashMap<SD, Vec<RouteCandidate>> {
+fn get_route_candidates_from_matrix(
+    link_matrix: &[Vec<bool>],
+    distance_matrix: Option<&[Vec<usize>]>,
+    setting: &RouteSearchSetting,
+) -> FxHashMap<SD, Vec<RouteCandidate>> {
 
     // グラフの作成
     let mut g = Graph::<usize, usize>::new();
@@ -65,13 +269,57 @@ fn get_route_candidates_from_matrix(link_matrix: &[Vec<bool>]) -> FxHashMap<SD,
     let edges = link_matrix_to_edges(link_matrix);
 
     for edge in edges {
-        g.add_edge(NodeIndex::new(edge.src.into()), NodeIndex::new(edge.dst.into()), 1);
+        let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+        // 距離による重み付けでない場合は全て1
+        let weight = distance_matrix.map_or(1, |d| d[src][dst]);
+        g.add_edge(NodeIndex::new(src), NodeIndex::new(dst), weight);
     }
     
-    get_route_cands_from_graph(g)
+    get_route_cands_from_graph_with_setting(g, setting)
 }
 
+/// 全SDペアについてSuurballeのアルゴリズムで独立な経路対を求める
+fn get_disjoint_route_pairs(
+    link_matrix: &[Vec<bool>],
+    distance_matrix: Option<&[Vec<usize>]>,
+    disjoint_type: DisjointType,
+) -> FxHashMap<SD, DisjointRoutePair> {
+    let node_count = link_matrix.len();
+    let arcs: Vec<(usize, usize, usize)> = link_matrix_to_edges(link_matrix)
+        .into_iter()
+        .map(|edge| {
+            let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+            (src, dst, distance_matrix.map_or(1, |d| d[src][dst]))
+        })
+        .collect();
+
+    let mut disjoint_pairs = FxHashMap::default();
+    for (src, dst) in iproduct!(0..node_count, 0..node_count).filter(|(s, d)| s != d) {
+        if let Some((primary, secondary)) = disjoint::suurballe(node_count, &arcs, src, dst, disjoint_type) {
+            disjoint_pairs.insert(
+                SD::new(src, dst),
+                DisjointRoutePair {
+                    primary: node_route_to_route_candidate(primary),
+                    secondary: node_route_to_route_candidate(secondary),
+                },
+            );
+        }
+    }
+
+    disjoint_pairs
+}
+
+fn node_route_to_route_candidate(node_route: Vec<usize>) -> RouteCandidate {
+    let edge_route = node_route.windows(2).map(|w| Edge::new(w[0], w[1])).collect();
+    RouteCandidate::new(node_route, edge_route)
+}
+
+/// 既定の設定 (Yen, ホップ数重み) で経路候補を求める
 pub fn get_route_cands_from_graph(g: Graph<usize, usize>) -> FxHashMap<SD, Vec<RouteCandidate>> {
+    get_route_cands_from_graph_with_setting(g, &RouteSearchSetting::default())
+}
+
+pub fn get_route_cands_from_graph_with_setting(g: Graph<usize, usize>, setting: &RouteSearchSetting) -> FxHashMap<SD, Vec<RouteCandidate>> {
     let sd_pairs: Vec<(NodeIndex, NodeIndex)> = iproduct!(g.node_indices(), g.node_indices()).filter(|(s,d)| s.index() != d.index()).collect();
     
     let pb = ProgressBar::new(sd_pairs.len() as u64);
@@ -79,58 +327,26 @@ pub fn get_route_cands_from_graph(g: Graph<usize, usize>) -> FxHashMap<SD, Vec<R
         ProgressStyle::default_bar().template(PB_TEMPLATES).unwrap().progress_chars(PB_CHARS)
     );
 
-    let pool = ThreadPoolBuilder::new()
-        .num_threads(THREADS)
-        .build()
-        .expect("Failed to create thread pool");
-    let route_candidates_vec: Vec<(SD, Vec<RouteCandidate>)> = pool.install(|| {
+    let route_candidates_vec: Vec<(SD, Vec<RouteCandidate>)> = utils::thread_pool().install(|| {
         sd_pairs.into_par_iter().map(|(src, dst)| {
             
-            let mut route_all: Vec<Vec<NodeIndex>> = vec![];
-            let mut route_length = 0;
-
-            let mut shortest_route_len = None;
-
-            while shortest_route_len.is_none() || route_length <= shortest_route_len.unwrap() + HOP_SLUG {
-                if g.node_count() < route_length {
-                    break;
-                }
-
-                let routes = all_simple_paths::<Vec<_>, _>(
-                    &g,
-                    src,
-                    dst,
-                    route_length,
-                    Some(route_length)
-                ).collect::<Vec<_>>();
-                
-                if shortest_route_len.is_none() && !routes.is_empty(){
-                    shortest_route_len = Some(route_length);
-                }
-
-                route_all.extend(routes);
-                route_length += 1;
-            }
+            let mut route_all: Vec<Vec<NodeIndex>> = match setting.method {
+                RouteSearchMethod::Yen => search_routes_by_yen(&g, src, dst, setting),
+                RouteSearchMethod::AllSimplePaths => search_routes_by_simple_paths(&g, src, dst, setting),
+            };
 
             if route_all.is_empty() {
                 pb.inc(1);
                 (SD::new(src.index(), dst.index()), vec![])
             } else {
                 // shortest_k 打ち切り
-                route_all.truncate(SHORTEST_K);
+                route_all.truncate(setting.k);
 
                 // debug::alert_route_cands_parameter
 
                 // hop_slug 打ち切り
-                let shortest_route_length = route_all[0].len();
-                let mut truncate_index = SHORTEST_K;
-                for (route_all_index, route) in route_all.iter().enumerate() {
-                    if route.len() > shortest_route_length + HOP_SLUG {
-                        truncate_index = route_all_index;
-                        break;
-                    }
-                }
-                route_all.truncate(truncate_index);
+                let shortest_route_length = route_all.iter().map(|route| route.len()).min().unwrap();
+                route_all.retain(|route| route.len() <= shortest_route_length + setting.hop_slack);
 
                 // NodeIndex > usize
                 let mut tmp_all: Vec<Vec<usize>> = vec![];
@@ -173,6 +389,48 @@ pub fn get_route_cands_from_graph(g: Graph<usize, usize>) -> FxHashMap<SD, Vec<R
     route_candidates
 }
 
+/// Yenのアルゴリズムにより経路候補をコスト順に求める
+/// ホップ数重みの場合は最短ホップ数 + hop_slack を超えた時点で打ち切る
+fn search_routes_by_yen(g: &Graph<usize, usize>, src: NodeIndex, dst: NodeIndex, setting: &RouteSearchSetting) -> Vec<Vec<NodeIndex>> {
+    let cost_slack = match setting.weight {
+        RouteWeight::Hop => Some(setting.hop_slack),
+        RouteWeight::Distance => None,
+    };
+
+    ksp::yen_k_shortest_paths(g, src, dst, setting.k, cost_slack)
+}
+
+/// all_simple_pathsにより最短ホップ数 + hop_slack までの経路を全列挙する
+fn search_routes_by_simple_paths(g: &Graph<usize, usize>, src: NodeIndex, dst: NodeIndex, setting: &RouteSearchSetting) -> Vec<Vec<NodeIndex>> {
+    let mut route_all: Vec<Vec<NodeIndex>> = vec![];
+    let mut route_length = 0;
+
+    let mut shortest_route_len = None;
+
+    while shortest_route_len.is_none() || route_length <= shortest_route_len.unwrap() + setting.hop_slack {
+        if g.node_count() < route_length {
+            break;
+        }
+
+        let routes = all_simple_paths::<Vec<_>, _>(
+            g,
+            src,
+            dst,
+            route_length,
+            Some(route_length)
+        ).collect::<Vec<_>>();
+        
+        if shortest_route_len.is_none() && !routes.is_empty(){
+            shortest_route_len = Some(route_length);
+        }
+
+        route_all.extend(routes);
+        route_length += 1;
+    }
+
+    route_all
+}
+
 fn link_matrix_to_edges(link_matrix: &[Vec<bool>]) -> Vec<Edge> {
     let mut o = vec![];
     for (r, l) in link_matrix.iter().enumerate() {
@@ -200,6 +458,65 @@ fn get_link_matrix(name: &str) -> Vec<Vec<bool>> {
     }
 }
 
+/// リンク距離行列を読み込む (`files/topology/{name}_distance.txt`)
+/// 隣接行列と同じ形式で，各要素はリンク距離 [km]
+fn get_distance_matrix(name: &str) -> Vec<Vec<usize>> {
+    let file_name = format!("./files/topology/{}_distance.txt", name).to_lowercase();
+    match File::open(file_name) {
+        Ok(mut file) => {
+            let mut content = String::new();
+            match file.read_to_string(&mut content) {
+                Ok(_) => utils::string_to_vec2_usize(&content),
+                Err(_) => panic!("ファイルを読み込めませんでした"),
+            }
+        }
+        Err(_) => panic!("距離ファイルを開けませんでした"),
+    }
+}
+
+/// SRLG定義を読み込む (`files/topology/{name}_srlg.txt`)
+/// 1行が1つのSRLGで，`0-1, 2-3` のようにリンクの両端ノードをカンマ区切りで並べる
+/// ファイルが存在しない場合はSRLGなしとする
+fn get_srlgs(name: &str) -> Vec<Vec<Edge>> {
+    let file_name = format!("./files/topology/{}_srlg.txt", name).to_lowercase();
+    let Ok(mut file) = File::open(file_name) else {
+        return vec![];
+    };
+
+    let mut content = String::new();
+    if file.read_to_string(&mut content).is_err() {
+        panic!("ファイルを読み込めませんでした");
+    }
+
+    content
+        .trim()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut srlg: Vec<Edge> = line
+                .split(',')
+                .map(|link| {
+                    let nodes: Vec<usize> = link
+                        .split('-')
+                        .map(|node| node.trim().parse::<usize>().expect("Invalid SRLG link"))
+                        .collect();
+                    assert_eq!(nodes.len(), 2, "Invalid SRLG link: {}", link);
+                    to_undirected(&Edge::new(nodes[0], nodes[1]))
+                })
+                .collect();
+            srlg.sort();
+            srlg.dedup();
+            srlg
+        })
+        .collect()
+}
+
+/// 方向を区別しないリンク (始点 < 終点)
+fn to_undirected(edge: &Edge) -> Edge {
+    let (src, dst): (usize, usize) = (edge.src.into(), edge.dst.into());
+    Edge::new(src.min(dst), src.max(dst))
+}
+
 pub fn get_ave_shortest_hops(topology: &Topology) -> f64 {
     let mut sum_hops = 0;
     for route_cands in topology.route_candidates.values() {
@@ -236,4 +553,78 @@ pub fn get_shortet_paths<'a>(topology: &'a Topology, sd: &'a SD, min_len: Option
     }
 
     route_cands_slices
-}
\ No newline at end of file
+}
+#[test]
+fn yen_matches_all_simple_paths_test() {
+    let link_matrix = get_link_matrix("jpn12");
+
+    let simple_paths = RouteSearchSetting { k: usize::MAX, ..Default::default() };
+    let yen = RouteSearchSetting { method: RouteSearchMethod::Yen, ..simple_paths.clone() };
+
+    let yen_cands = get_route_candidates_from_matrix(&link_matrix, None, &yen);
+    let simple_paths_cands = get_route_candidates_from_matrix(&link_matrix, None, &simple_paths);
+
+    for (sd, cands) in &simple_paths_cands {
+        let mut expected: Vec<&Vec<usize>> = cands.iter().map(|c| &c.node_route).collect();
+        let mut actual: Vec<&Vec<usize>> = yen_cands[sd].iter().map(|c| &c.node_route).collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual, "{sd}");
+    }
+}
+
+#[test]
+fn disjoint_route_pair_test() {
+    let link_matrix = get_link_matrix("jpn12");
+    let link_disjoint_pairs = get_disjoint_route_pairs(&link_matrix, None, DisjointType::Link);
+    let node_disjoint_pairs = get_disjoint_route_pairs(&link_matrix, None, DisjointType::Node);
+
+    for (sd, pair) in &link_disjoint_pairs {
+        let (src, dst) = sd.into();
+        for route in [&pair.primary, &pair.secondary] {
+            assert_eq!(route.node_route.first(), Some(&src.into()));
+            assert_eq!(route.node_route.last(), Some(&dst.into()));
+        }
+        for edge in &pair.primary.edge_route {
+            let reversed = Edge::new(edge.dst.into(), edge.src.into());
+            assert!(!pair.secondary.edge_route.contains(edge) && !pair.secondary.edge_route.contains(&reversed), "{sd}");
+        }
+    }
+
+    for (sd, pair) in &node_disjoint_pairs {
+        let inner_nodes = &pair.primary.node_route[1..pair.primary.node_route.len() - 1];
+        assert!(inner_nodes.iter().all(|n| !pair.secondary.node_route.contains(n)), "{sd}");
+        assert!(link_disjoint_pairs.contains_key(sd));
+    }
+}
+
+#[test]
+fn srlg_disjoint_route_pair_test() {
+    let link_matrix = get_link_matrix("jpn12");
+    let route_candidates = get_route_candidates_from_matrix(&link_matrix, None, &RouteSearchSetting::default());
+    let link_disjoint_pairs = get_disjoint_route_pairs(&link_matrix, None, DisjointType::Link);
+
+    // ノード0の2本のリンクは同じ管路を通る
+    let srlgs = vec![
+        vec![Edge::new(0, 1), Edge::new(0, 3)],
+        vec![Edge::new(2, 6), Edge::new(3, 5)],
+    ];
+    let topology = Topology {
+        name: "jpn12".to_string(),
+        edges: link_matrix_to_edges(&link_matrix),
+        link_matrix,
+        route_candidates,
+        link_disjoint_pairs: FxHashMap::default(),
+        node_disjoint_pairs: FxHashMap::default(),
+        srlgs,
+    };
+    let srlg_disjoint_pairs = topology.get_srlg_disjoint_route_pairs(&link_disjoint_pairs, None, DisjointType::Link);
+
+    assert!(!srlg_disjoint_pairs.is_empty());
+    for (sd, pair) in &srlg_disjoint_pairs {
+        let (src, dst) = sd.into();
+        assert!(usize::from(src) != 0 && usize::from(dst) != 0, "{sd}");
+        assert!(pair.primary.is_disjoint_with(&pair.secondary, DisjointType::Link), "{sd}");
+        assert!(topology.is_srlg_disjoint(&pair.primary, &pair.secondary), "{sd}");
+    }
+}
@@ -0,0 +1,183 @@
+use std::{ cmp::Reverse, collections::BinaryHeap };
+
+use fxhash::{ FxHashMap, FxHashSet };
+
+/// 経路対の独立性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisjointType {
+    /// リンク(双方向)を共有しない
+    Link,
+    /// 始点・終点以外のノードを共有しない
+    Node,
+}
+
+/// Suurballeのアルゴリズムにより，総コスト最小の独立な経路対を求める
+///
+/// `arcs`は (始点, 終点, 重み) の有向リンク．物理リンクは双方向のリンク対として与えること．
+/// 戻り値はコストの小さい順の (主経路, 副経路) のノード列．
+pub fn suurballe(
+    node_count: usize,
+    arcs: &[(usize, usize, usize)],
+    src: usize,
+    dst: usize,
+    disjoint_type: DisjointType,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    match disjoint_type {
+        DisjointType::Link => {
+            let (first, second) = suurballe_arc_disjoint(node_count, arcs, src, dst, true)?;
+            Some(sort_by_cost(arcs, first, second))
+        }
+        DisjointType::Node => {
+            // ノード分割: v -> (v_in = 2v, v_out = 2v + 1)
+            // 始点はv_out，終点はv_inを用いる
+            let mut split_arcs = vec![];
+            for v in 0..node_count {
+                split_arcs.push((2 * v, 2 * v + 1, 0));
+            }
+            for &(u, v, w) in arcs {
+                split_arcs.push((2 * u + 1, 2 * v, w));
+            }
+
+            let (first, second) = suurballe_arc_disjoint(2 * node_count, &split_arcs, 2 * src + 1, 2 * dst, false)?;
+            let merge = |path: Vec<usize>| -> Vec<usize> {
+                let mut out: Vec<usize> = vec![];
+                for n in path {
+                    if out.last() != Some(&(n / 2)) {
+                        out.push(n / 2);
+                    }
+                }
+                out
+            };
+
+            Some(sort_by_cost(arcs, merge(first), merge(second)))
+        }
+    }
+}
+
+/// 有向リンクを共有しない経路対
+/// `undirected`が真の場合，逆向きのリンクも同じリンクとみなす
+fn suurballe_arc_disjoint(
+    node_count: usize,
+    arcs: &[(usize, usize, usize)],
+    src: usize,
+    dst: usize,
+    undirected: bool,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    // 1回目の最短経路
+    let adjacency = to_adjacency(node_count, arcs.iter().copied());
+    let (dist, prev) = dijkstra(&adjacency, src);
+    dist[dst]?;
+    let first_path = trace(&prev, src, dst);
+    let first_arcs: FxHashSet<(usize, usize)> = first_path.windows(2).map(|w| (w[0], w[1])).collect();
+
+    // 縮約コストによる残余グラフ
+    let residual_arcs = arcs
+        .iter()
+        .filter(|(u, v, _)| {
+            !(first_arcs.contains(&(*u, *v)) || undirected && first_arcs.contains(&(*v, *u)))
+        })
+        .filter_map(|&(u, v, w)| match (dist[u], dist[v]) {
+            (Some(du), Some(dv)) => Some((u, v, w + du - dv)),
+            _ => None,
+        })
+        .chain(first_arcs.iter().map(|&(u, v)| (v, u, 0)));
+    let residual_adjacency = to_adjacency(node_count, residual_arcs);
+
+    // 2回目の最短経路
+    let (residual_dist, residual_prev) = dijkstra(&residual_adjacency, src);
+    residual_dist[dst]?;
+    let second_path = trace(&residual_prev, src, dst);
+
+    // 逆向きに使用されたリンクを相殺
+    let mut used_arcs: FxHashMap<usize, Vec<usize>> = FxHashMap::default();
+    let second_arcs: FxHashSet<(usize, usize)> = second_path.windows(2).map(|w| (w[0], w[1])).collect();
+    for w in first_path.windows(2) {
+        let (u, v) = (w[0], w[1]);
+        if !second_arcs.contains(&(v, u)) {
+            used_arcs.entry(u).or_default().push(v);
+        }
+    }
+    for w in second_path.windows(2) {
+        let (u, v) = (w[0], w[1]);
+        if !first_arcs.contains(&(v, u)) {
+            used_arcs.entry(u).or_default().push(v);
+        }
+    }
+
+    let mut paths = vec![];
+    for _ in 0..2 {
+        let mut path = vec![src];
+        let mut node = src;
+        while node != dst {
+            node = used_arcs.get_mut(&node)?.pop()?;
+            path.push(node);
+        }
+        paths.push(path);
+    }
+    let second = paths.pop().unwrap();
+    let first = paths.pop().unwrap();
+
+    Some((first, second))
+}
+
+fn to_adjacency(node_count: usize, arcs: impl Iterator<Item = (usize, usize, usize)>) -> Vec<Vec<(usize, usize)>> {
+    let mut adjacency = vec![vec![]; node_count];
+    for (u, v, w) in arcs {
+        adjacency[u].push((v, w));
+    }
+    for neighbors in adjacency.iter_mut() {
+        neighbors.sort();
+    }
+    adjacency
+}
+
+fn dijkstra(adjacency: &[Vec<(usize, usize)>], src: usize) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let mut dist: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut heap = BinaryHeap::new();
+
+    dist[src] = Some(0);
+    heap.push(Reverse((0, src)));
+
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if dist[node].is_some_and(|d| d < cost) {
+            continue;
+        }
+
+        for &(next, w) in &adjacency[node] {
+            let next_cost = cost + w;
+            if dist[next].is_none_or(|d| next_cost < d) {
+                dist[next] = Some(next_cost);
+                prev[next] = Some(node);
+                heap.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+fn trace(prev: &[Option<usize>], src: usize, dst: usize) -> Vec<usize> {
+    let mut path = vec![dst];
+    let mut node = dst;
+    while node != src {
+        node = prev[node].unwrap();
+        path.push(node);
+    }
+    path.reverse();
+    path
+}
+
+fn sort_by_cost(arcs: &[(usize, usize, usize)], first: Vec<usize>, second: Vec<usize>) -> (Vec<usize>, Vec<usize>) {
+    let cost = |path: &[usize]| -> usize {
+        path.windows(2)
+            .map(|w| arcs.iter().find(|(u, v, _)| *u == w[0] && *v == w[1]).unwrap().2)
+            .sum()
+    };
+
+    if (cost(&second), second.len()) < (cost(&first), first.len()) {
+        (second, first)
+    } else {
+        (first, second)
+    }
+}
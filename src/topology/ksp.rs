@@ -0,0 +1,134 @@
+use std::{ cmp::Reverse, collections::BinaryHeap };
+
+use fxhash::{ FxHashMap, FxHashSet };
+use petgraph::{ graph::{ Graph, NodeIndex }, visit::EdgeRef };
+
+/// Yenのアルゴリズムによりsrc-dst間の経路をコストの小さい順に最大k本求める
+///
+/// `cost_slack`を指定した場合，最短経路のコスト + `cost_slack`を超える経路が出た時点で探索を打ち切る．
+/// コストが同じ経路はノード列の辞書順に並ぶ．
+pub fn yen_k_shortest_paths(
+    g: &Graph<usize, usize>,
+    src: NodeIndex,
+    dst: NodeIndex,
+    k: usize,
+    cost_slack: Option<usize>,
+) -> Vec<Vec<NodeIndex>> {
+    let mut fixed_paths: Vec<(usize, Vec<NodeIndex>)> = vec![];
+
+    let Some(shortest) = dijkstra(g, src, dst, &FxHashSet::default(), &FxHashSet::default()) else {
+        return vec![];
+    };
+    let max_cost = cost_slack.map(|slack| shortest.0 + slack);
+
+    let mut candidates: BinaryHeap<Reverse<(usize, Vec<usize>)>> = BinaryHeap::new();
+    let mut seen_paths: FxHashSet<Vec<NodeIndex>> = FxHashSet::default();
+    seen_paths.insert(shortest.1.clone());
+    fixed_paths.push(shortest);
+
+    while fixed_paths.len() < k {
+        let last_path = fixed_paths.last().unwrap().1.clone();
+
+        for i in 0..last_path.len() - 1 {
+            let spur_node = last_path[i];
+            let root_path = &last_path[..=i];
+
+            // 同じ根経路を持つ確定済み経路の次のリンクを除外
+            let mut removed_edges = FxHashSet::default();
+            for (_, path) in &fixed_paths {
+                if path.len() > i + 1 && &path[..=i] == root_path {
+                    removed_edges.insert((path[i], path[i + 1]));
+                }
+            }
+
+            // 根経路上のノードを除外 (ループ防止)
+            let removed_nodes: FxHashSet<NodeIndex> = root_path[..i].iter().copied().collect();
+
+            if let Some((spur_cost, spur_path)) = dijkstra(g, spur_node, dst, &removed_nodes, &removed_edges) {
+                let mut total_path = root_path.to_vec();
+                total_path.extend_from_slice(&spur_path[1..]);
+
+                if seen_paths.insert(total_path.clone()) {
+                    let total_cost = path_cost(g, root_path) + spur_cost;
+                    let node_indices = total_path.iter().map(|n| n.index()).collect();
+                    candidates.push(Reverse((total_cost, node_indices)));
+                }
+            }
+        }
+
+        match candidates.pop() {
+            Some(Reverse((cost, path))) => {
+                if max_cost.is_some_and(|max_cost| cost > max_cost) {
+                    break;
+                }
+                fixed_paths.push((cost, path.into_iter().map(NodeIndex::new).collect()));
+            }
+            None => break,
+        }
+    }
+
+    fixed_paths.into_iter().map(|(_, path)| path).collect()
+}
+
+/// 除外ノード・除外リンクを考慮したダイクストラ法
+fn dijkstra(
+    g: &Graph<usize, usize>,
+    src: NodeIndex,
+    dst: NodeIndex,
+    removed_nodes: &FxHashSet<NodeIndex>,
+    removed_edges: &FxHashSet<(NodeIndex, NodeIndex)>,
+) -> Option<(usize, Vec<NodeIndex>)> {
+    let mut costs: FxHashMap<NodeIndex, usize> = FxHashMap::default();
+    let mut prev: FxHashMap<NodeIndex, NodeIndex> = FxHashMap::default();
+    let mut heap = BinaryHeap::new();
+
+    costs.insert(src, 0);
+    heap.push(Reverse((0, src.index())));
+
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        let node = NodeIndex::new(node);
+        if node == dst {
+            break;
+        }
+        if costs.get(&node).is_some_and(|c| *c < cost) {
+            continue;
+        }
+
+        for edge in g.edges(node) {
+            let next = edge.target();
+            if removed_nodes.contains(&next) || removed_edges.contains(&(node, next)) {
+                continue;
+            }
+
+            let next_cost = cost + *edge.weight();
+            let is_better = match costs.get(&next) {
+                // 同コストの場合は番号の小さいノードを経由する経路を優先
+                Some(c) => next_cost < *c || (next_cost == *c && prev.get(&next).is_some_and(|p| node.index() < p.index())),
+                None => true,
+            };
+            if is_better {
+                costs.insert(next, next_cost);
+                prev.insert(next, node);
+                heap.push(Reverse((next_cost, next.index())));
+            }
+        }
+    }
+
+    let cost = *costs.get(&dst)?;
+
+    let mut path = vec![dst];
+    let mut node = dst;
+    while node != src {
+        node = prev[&node];
+        path.push(node);
+    }
+    path.reverse();
+
+    Some((cost, path))
+}
+
+fn path_cost(g: &Graph<usize, usize>, path: &[NodeIndex]) -> usize {
+    path.windows(2)
+        .map(|w| *g.edge_weight(g.find_edge(w[0], w[1]).unwrap()).unwrap())
+        .sum()
+}
@@ -1,6 +1,8 @@
 use chrono::{ DateTime, Local };
-use std::{ fs, io::Error, process, time::SystemTime };
-use uuid::Uuid;
+use rayon::{ ThreadPool, ThreadPoolBuilder };
+use std::{ fs, io::Error, process, sync::OnceLock, time::SystemTime };
+
+use crate::np_core::parameters::THREADS;
 
 mod output;
 pub use output::*;
@@ -15,6 +17,18 @@ pub fn read_file(filepath: &str) -> Result<String, Error> {
     Ok(file_contents)
 }
 
+/// 並列処理に用いる`THREADS`スレッドのスレッドプール
+/// プールを共有するため，並列処理の中から呼ばれた処理も新たなスレッドを作らずに同じプールで実行される
+pub fn thread_pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        ThreadPoolBuilder::new()
+            .num_threads(THREADS)
+            .build()
+            .expect("Failed to create thread pool")
+    })
+}
+
 /// タイムスタンプとUUIDの一部を使用し、IDを生成する
 /// 形式は、%Y%m%d/%H%M%S_0%3f_UUID
 pub fn generate_id() -> String {
@@ -29,11 +43,6 @@ pub fn generate_id() -> String {
     pid_str.to_string()
 }
 
-/// UUIDを生成する
-pub fn generate_uuid() -> Uuid {
-    Uuid::now_v7()
-}
-
 /// CSV形式の二次元配列を読み込む
 pub fn string_to_vec2_bool(data: &str) -> Vec<Vec<bool>> {
     let mut o = vec![];
@@ -56,6 +65,26 @@ pub fn string_to_vec2_bool(data: &str) -> Vec<Vec<bool>> {
     o
 }
 
+/// CSV形式の二次元配列を読み込む
+/// 数値として読み込めない要素があれば，行番号とともにpanicする
+pub fn string_to_vec2_usize(data: &str) -> Vec<Vec<usize>> {
+    let mut o = vec![];
+
+    for (line_index, l) in data.trim().lines().enumerate() {
+        let mut r = vec![];
+        for v in l.trim().split(',') {
+            match v.trim().parse::<usize>() {
+                Ok(b) => r.push(b),
+                Err(_) => panic!("{}行目の`{}`を数値として読み込めません", line_index + 1, v.trim()),
+            }
+        }
+
+        o.push(r);
+    }
+
+    o
+}
+
 pub fn find_x_for_y(x_y: &[(f64, f64)], target_y: f64) -> Option<f64> {
     for i in 0..x_y.len() - 1 {
         let x0 = x_y[i].0;
@@ -76,4 +105,10 @@ pub fn find_x_for_y(x_y: &[(f64, f64)], target_y: f64) -> Option<f64> {
 pub fn contains_subslice<T: PartialEq>(main_slice: &[T], sub_slice: &[T]) -> bool {
     // main_sliceをsub_sliceの長さのウィンドウでスライドしながら部分一致を探す
     main_slice.windows(sub_slice.len()).any(|window| window == sub_slice)
-}
\ No newline at end of file
+}
+#[test]
+#[should_panic(expected = "2行目の`x`")]
+fn string_to_vec2_usize_invalid_token_test() {
+    assert_eq!(string_to_vec2_usize("0, 10\n10, 0"), vec![vec![0, 10], vec![10, 0]]);
+    string_to_vec2_usize("0, 10\n10, x");
+}